@@ -0,0 +1,214 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    definition::ChainExtensionDefinition,
+    format_err_spanned,
+    types::AttributeArgs,
+};
+use proc_macro2::TokenStream;
+use quote::{
+    quote,
+    ToTokens,
+};
+use syn::{
+    parse2,
+    parse_quote,
+    Attribute,
+    Error,
+    FnArg,
+    ItemTrait,
+    Lit,
+    Meta,
+    NestedMeta,
+    ReturnType,
+    TraitItem,
+    TraitItemMethod,
+    Type,
+};
+
+/// Converter of the ink! 3 `#[ink::chain_extension]` trait into the OBCE definition.
+///
+/// Ids of methods are kept as `raw_id` of the legacy definition and `type ErrorCode` becomes
+/// `error_code`, so the converted definition uses the same ABI as the original chain extension.
+pub struct ChainExtensionConversion;
+
+impl ChainExtensionConversion {
+    /// Generates the OBCE definition from the ink! chain extension.
+    pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
+        let (attrs, trait_item) = Self::definition(attrs, input)?;
+
+        ChainExtensionDefinition::generate(attrs, trait_item.into_token_stream())
+    }
+
+    fn definition(attrs: TokenStream, input: TokenStream) -> Result<(TokenStream, ItemTrait), Error> {
+        let mut trait_item: ItemTrait = parse2(input)?;
+        trait_item
+            .attrs
+            .retain(|attr| !is_ink_attribute(attr, "chain_extension"));
+
+        let error_code = error_code(&trait_item)?;
+        let mut items = vec![];
+        for item in trait_item.items.iter() {
+            match item {
+                TraitItem::Type(ty) if ty.ident == "ErrorCode" => {}
+                TraitItem::Method(method) => {
+                    let mut method = method.clone();
+                    let config = MethodConfig::new(&method)?;
+                    method.attrs.retain(|attr| !attr.path.is_ident("ink"));
+
+                    if let Some(FnArg::Receiver(receiver)) = method.sig.inputs.first() {
+                        return Err(format_err_spanned!(
+                            receiver,
+                            "methods of ink! chain extension don't have a receiver"
+                        ))
+                    }
+                    method.sig.inputs.insert(0, parse_quote! { &mut self });
+
+                    let raw_id = config.extension;
+                    if config.handle_status && config.returns_result {
+                        // ink! decodes the whole `Result` from the output of the successful call.
+                        method
+                            .attrs
+                            .push(parse_quote! { #[obce(raw_id = #raw_id, output_result)] });
+                    } else if config.handle_status {
+                        // The output of the method is wrapped into the result with the error code.
+                        let output = match &method.sig.output {
+                            ReturnType::Type(_, ty) => quote! { #ty },
+                            ReturnType::Default => quote! { () },
+                        };
+                        method.sig.output = parse_quote! {
+                            -> ::core::result::Result<#output, #error_code>
+                        };
+                        method.attrs.push(parse_quote! { #[obce(raw_id = #raw_id)] });
+                    } else {
+                        method
+                            .attrs
+                            .push(parse_quote! { #[obce(raw_id = #raw_id, handle_status = false)] });
+                    }
+                    items.push(TraitItem::Method(method));
+                }
+                _ => {
+                    return Err(format_err_spanned!(
+                        item,
+                        "only methods and `type ErrorCode` are supported in ink! chain extension",
+                    ))
+                }
+            }
+        }
+        trait_item.items = items;
+
+        let attrs = if attrs.is_empty() {
+            quote! { legacy, error_code = #error_code }
+        } else {
+            quote! { legacy, error_code = #error_code, #attrs }
+        };
+
+        Ok((attrs, trait_item))
+    }
+}
+
+/// Arguments of `#[ink(..)]` attributes of the chain extension's method.
+struct MethodConfig {
+    extension: u32,
+    handle_status: bool,
+    returns_result: bool,
+}
+
+impl MethodConfig {
+    fn new(method: &TraitItemMethod) -> Result<Self, Error> {
+        let mut extension = None;
+        let mut handle_status = true;
+        let mut returns_result = true;
+
+        for attr in method.attrs.iter().filter(|attr| attr.path.is_ident("ink")) {
+            let attrs = attr
+                .parse_args::<AttributeArgs>()
+                .map_err(|error| format_err_spanned!(attr, "unable decode attributes: {}", error,))?;
+
+            for arg in attrs.iter() {
+                match arg {
+                    NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("extension") => {
+                        if let Lit::Int(lit_id) = &value.lit {
+                            extension = Some(lit_id.base10_parse::<u32>().map_err(|error| {
+                                format_err_spanned!(
+                                    value.lit,
+                                    "extension out of range. extension must be a valid `u32` integer: {}",
+                                    error,
+                                )
+                            })?);
+                        } else {
+                            Err(format_err_spanned!(value, "extension should be integer"))?;
+                        }
+                    }
+                    NestedMeta::Meta(Meta::NameValue(value))
+                        if value.path.is_ident("handle_status") || value.path.is_ident("returns_result") =>
+                    {
+                        if let Lit::Bool(lit_bool) = &value.lit {
+                            if value.path.is_ident("handle_status") {
+                                handle_status = lit_bool.value;
+                            } else {
+                                returns_result = lit_bool.value;
+                            }
+                        } else {
+                            Err(format_err_spanned!(value, "value should be boolean"))?;
+                        }
+                    }
+                    _ => {
+                        Err(format_err_spanned!(arg, "unexpected attribute"))?;
+                    }
+                }
+            }
+        }
+
+        let extension = extension
+            .ok_or_else(|| format_err_spanned!(method.sig.ident, "method should have `#[ink(extension = N)]`"))?;
+
+        Ok(Self {
+            extension,
+            handle_status,
+            returns_result,
+        })
+    }
+}
+
+/// Returns the type of `type ErrorCode = ..` of the ink! chain extension.
+fn error_code(trait_item: &ItemTrait) -> Result<Type, Error> {
+    for item in trait_item.items.iter() {
+        if let TraitItem::Type(ty) = item {
+            if ty.ident == "ErrorCode" {
+                let (_, error_code) = ty.default.as_ref().ok_or_else(|| {
+                    format_err_spanned!(ty, "`type ErrorCode` should have the type of the error code")
+                })?;
+                return Ok(error_code.clone())
+            }
+        }
+    }
+    Err(format_err_spanned!(
+        trait_item.ident,
+        "ink! chain extension should define `type ErrorCode`"
+    ))
+}
+
+fn is_ink_attribute(attr: &Attribute, name: &str) -> bool {
+    let segments = &attr.path.segments;
+    segments.len() == 2 && segments[0].ident == "ink" && segments[1].ident == name
+}
@@ -21,6 +21,7 @@
 
 use crate::{
     format_err_spanned,
+    lock,
     types::AttributeArgs,
     utils::{
         into_u16,
@@ -28,40 +29,153 @@ use crate::{
     },
 };
 use proc_macro2::TokenStream;
-use quote::quote;
+use quote::{
+    format_ident,
+    quote,
+    quote_spanned,
+    ToTokens,
+};
 use syn::{
     parse2,
+    parse_quote,
+    parse_quote_spanned,
+    spanned::Spanned,
+    Attribute,
     Error,
+    Expr,
+    File,
+    FnArg,
+    GenericArgument,
+    Item,
     ItemTrait,
     Lit,
+    LitInt,
     Meta,
     NestedMeta,
+    PatType,
+    Path,
+    PathArguments,
     ReturnType,
     TraitItem,
     TraitItemMethod,
+    Type,
+    Visibility,
 };
 
 struct Config {
     id: u16,
+    output_weight_per_byte: Option<u64>,
+    ink_cfg: Option<TokenStream>,
+    substrate_cfg: Option<TokenStream>,
+    both: bool,
+    lock: Option<String>,
+    legacy: bool,
+    error_code: Option<Type>,
+    allow: Option<Path>,
 }
 
 impl Config {
     fn new(trait_item: &ItemTrait, attrs: TokenStream) -> Result<Self, Error> {
         let mut config = Self {
             id: into_u16(&trait_item.ident),
+            output_weight_per_byte: None,
+            ink_cfg: None,
+            substrate_cfg: None,
+            both: false,
+            lock: None,
+            legacy: false,
+            error_code: None,
+            allow: None,
         };
         config.parse_attributes(attrs)?;
+
+        // Ids of legacy `#[ink::chain_extension]` contain the extension id in the upper half.
+        if config.legacy {
+            let mut extension_id = None;
+            for item in trait_item.items.iter() {
+                if let TraitItem::Method(method) = item {
+                    let raw_id = MethodConfig::new(method)?.raw_id.ok_or_else(|| {
+                        format_err_spanned!(
+                            method.sig.ident,
+                            "methods of the legacy definition should have `raw_id`"
+                        )
+                    })?;
+                    let id = (raw_id >> 16) as u16;
+                    match extension_id {
+                        Some(extension_id) if extension_id != id => {
+                            return Err(format_err_spanned!(
+                                method.sig.ident,
+                                "`raw_id` should have the same upper 16 bits as other methods: `{:#x}`",
+                                extension_id,
+                            ))
+                        }
+                        _ => extension_id = Some(id),
+                    }
+                }
+            }
+            config.id = extension_id.unwrap_or_default();
+        }
         Ok(config)
     }
 
-    fn parse_attributes(&mut self, attrs: TokenStream) -> Result<(), Error> {
-        let attrs = parse2::<AttributeArgs>(attrs.clone())
-            .map_err(|error| format_err_spanned!(attrs, "unable decode attributes: {}", error,))?;
+    fn parse_attributes(&mut self, tokens: TokenStream) -> Result<(), Error> {
+        let attrs = parse2::<AttributeArgs>(tokens.clone())
+            .map_err(|error| format_err_spanned!(tokens, "unable decode attributes: {}", error,))?;
 
-        let id = extract_attributes(attrs)?;
-        if let Some(id) = id {
+        let attrs = extract_attributes(attrs)?;
+        if attrs.view {
+            return Err(format_err_spanned!(tokens, "`view` is supported only by methods"))
+        }
+        if attrs.ink_type.is_some() || attrs.substrate_type.is_some() {
+            return Err(format_err_spanned!(
+                tokens,
+                "`ink_type` and `substrate_type` are supported only by methods and arguments"
+            ))
+        }
+        if !attrs.aliases.is_empty() {
+            return Err(format_err_spanned!(tokens, "`aliases` are supported only by methods"))
+        }
+        if attrs.raw_id.is_some() || attrs.handle_status.is_some() || attrs.output_result {
+            return Err(format_err_spanned!(
+                tokens,
+                "`raw_id`, `handle_status` and `output_result` are supported only by methods"
+            ))
+        }
+        if attrs.legacy && attrs.id.is_some() {
+            return Err(format_err_spanned!(
+                tokens,
+                "the id of the legacy definition is defined by `raw_id` of methods"
+            ))
+        }
+        if attrs.is_validation() {
+            return Err(format_err_spanned!(
+                tokens,
+                "`validate`, `range` and `max_len` are supported only by arguments"
+            ))
+        }
+        if let Some(id) = attrs.id {
             self.id = id;
         }
+        if let Some(weight) = attrs.output_weight_per_byte {
+            self.output_weight_per_byte = Some(weight);
+        }
+        if let Some(cfg) = attrs.ink_cfg {
+            self.ink_cfg = Some(cfg);
+        }
+        if let Some(cfg) = attrs.substrate_cfg {
+            self.substrate_cfg = Some(cfg);
+        }
+        self.both |= attrs.both;
+        self.legacy |= attrs.legacy;
+        if let Some(error_code) = attrs.error_code {
+            self.error_code = Some(error_code);
+        }
+        if let Some(lock) = attrs.lock {
+            self.lock = Some(lock);
+        }
+        if let Some(provider) = attrs.allow {
+            self.allow = Some(provider);
+        }
 
         Ok(())
     }
@@ -71,6 +185,14 @@ struct MethodConfig {
     id: u16,
     input: TokenStream,
     output: TokenStream,
+    output_weight_per_byte: Option<u64>,
+    view: bool,
+    ink_output: Option<Type>,
+    substrate_output: Option<Type>,
+    aliases: Vec<u16>,
+    raw_id: Option<u32>,
+    handle_status: Option<bool>,
+    output_result: bool,
 }
 
 impl MethodConfig {
@@ -95,24 +217,74 @@ impl MethodConfig {
             output: quote! {
                 #output
             },
+            output_weight_per_byte: None,
+            view: false,
+            ink_output: None,
+            substrate_output: None,
+            aliases: vec![],
+            raw_id: None,
+            handle_status: None,
+            output_result: false,
         };
         config.parse_attributes(method_item)?;
         Ok(config)
     }
 
     fn parse_attributes(&mut self, method_item: &TraitItemMethod) -> Result<(), Error> {
+        // The id may be set by one `#[obce(..)]` and the raw id by another one.
+        let mut has_id = false;
         for attr in method_item.attrs.iter() {
             if !attr.path.is_ident("obce") {
                 continue
             }
 
-            let attrs = parse2::<AttributeArgs>(attr.tokens.clone())
+            let attrs = attr
+                .parse_args::<AttributeArgs>()
                 .map_err(|error| format_err_spanned!(attr, "unable decode attributes: {}", error,))?;
 
-            let id = extract_attributes(attrs)?;
-            if let Some(id) = id {
+            let attrs = extract_attributes(attrs)?;
+            if attrs.is_trait_only() {
+                return Err(format_err_spanned!(
+                    attr,
+                    "`ink_cfg`, `substrate_cfg`, `both`, `lock`, `legacy`, `error_code` and `allow` are supported only \
+                     by the trait"
+                ))
+            }
+            if attrs.is_validation() {
+                return Err(format_err_spanned!(
+                    attr,
+                    "`validate`, `range` and `max_len` are supported only by arguments"
+                ))
+            }
+            if let Some(id) = attrs.id {
+                if self.raw_id.is_some() {
+                    return Err(format_err_spanned!(attr, "`id` and `raw_id` can't be used together"))
+                }
                 self.id = id;
+                has_id = true;
+            }
+            if let Some(weight) = attrs.output_weight_per_byte {
+                self.output_weight_per_byte = Some(weight);
+            }
+            self.view |= attrs.view;
+            if let Some(ty) = attrs.ink_type {
+                self.ink_output = Some(ty);
+            }
+            if let Some(ty) = attrs.substrate_type {
+                self.substrate_output = Some(ty);
             }
+            self.aliases.extend(attrs.aliases);
+            if let Some(raw_id) = attrs.raw_id {
+                if has_id {
+                    return Err(format_err_spanned!(attr, "`id` and `raw_id` can't be used together"))
+                }
+                self.id = raw_id as u16;
+                self.raw_id = Some(raw_id);
+            }
+            if let Some(handle_status) = attrs.handle_status {
+                self.handle_status = Some(handle_status);
+            }
+            self.output_result |= attrs.output_result;
         }
         Ok(())
     }
@@ -123,7 +295,7 @@ pub struct ChainExtensionDefinition;
 impl ChainExtensionDefinition {
     pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
         let mut trait_item: ItemTrait = parse2(input).unwrap();
-        let config = Config::new(&trait_item, attrs)?;
+        let trait_config = Config::new(&trait_item, attrs)?;
         let trait_name = trait_item.ident.clone();
 
         let mut methods = vec![];
@@ -139,6 +311,9 @@ impl ChainExtensionDefinition {
         }
 
         let mut method_descriptions = vec![];
+        let mut methods_metadata = vec![];
+        let mut used_ids = vec![];
+        let mut locked_ids = vec![];
         for method in methods {
             if let Some(default) = method.default {
                 return Err(format_err_spanned!(
@@ -148,76 +323,225 @@ impl ChainExtensionDefinition {
             }
 
             let config = MethodConfig::new(&method)?;
+            if config.raw_id.is_some() && !trait_config.legacy {
+                return Err(format_err_spanned!(
+                    method.sig.ident,
+                    "`raw_id` is supported only by `#[obce::definition(legacy)]`",
+                ))
+            }
+            // Aliases are matched on the substrate side in the same way as ids.
+            for id in core::iter::once(config.id).chain(config.aliases.iter().copied()) {
+                if id == u16::MAX {
+                    return Err(format_err_spanned!(
+                        method.sig.ident,
+                        "id `{:#x}` is reserved for the batch call",
+                        id,
+                    ))
+                }
+                if used_ids.contains(&id) {
+                    return Err(format_err_spanned!(
+                        method.sig.ident,
+                        "id `{:#x}` is already used by another method or alias",
+                        id,
+                    ))
+                }
+                used_ids.push(id);
+            }
+
             let hash = into_u32(&method.sig.ident);
             let id = config.id;
+            // Legacy methods are locked by raw ids, as they are used by deployed contracts.
+            let lock_id = |id: u16| {
+                if trait_config.legacy {
+                    ((trait_config.id as u32) << 16) | id as u32
+                } else {
+                    id as u32
+                }
+            };
+            let ids = core::iter::once(id).chain(config.aliases.iter().copied());
+            locked_ids.push((method.sig.ident.to_string(), ids.map(lock_id).collect()));
+            let aliases = config.aliases;
             let input = config.input;
             let output = config.output;
+            let output_weight_per_byte = match config.output_weight_per_byte.or(trait_config.output_weight_per_byte) {
+                Some(weight) => quote! { ::core::option::Option::Some(#weight) },
+                None => quote! { ::core::option::Option::None },
+            };
+            let view = config.view;
+            let handle_status = trait_config.error_code.is_some() && config.handle_status != Some(false);
             let (impls, types, where_clause) = trait_item.generics.split_for_impl();
-            // TODO: Add check that each `id` is unique
             method_descriptions.push(quote! {
                 impl #impls ::obce::codegen::MethodDescription<#hash> for dyn #trait_name #types #where_clause {
                     const ID: ::core::primitive::u16 = #id;
+                    const OUTPUT_WEIGHT_PER_BYTE: ::core::option::Option<::core::primitive::u64> = #output_weight_per_byte;
+                    const VIEW: ::core::primitive::bool = #view;
+                    const ALIASES: &'static [::core::primitive::u16] = &[#(#aliases),*];
+                    const HANDLE_STATUS: ::core::primitive::bool = #handle_status;
                     type Input = #input;
                     type Output = #output;
                 }
             });
+
+            let name = method.sig.ident.to_string();
+            methods_metadata.push(quote! {
+                ::obce::codegen::MethodMetadata {
+                    name: #name,
+                    id: #id,
+                    view: #view,
+                }
+            });
         }
 
+        let lock = match &trait_config.lock {
+            Some(path) => lock::check_ids(&trait_name, path, &trait_name.to_string(), trait_config.id, &locked_ids)?,
+            None => quote! {},
+        };
+
+        // Items of both sides are placed into submodules, so they should be visible outside.
+        if trait_config.both && matches!(trait_item.vis, Visibility::Inherited) {
+            trait_item.vis = parse_quote! { pub(super) };
+        }
+
+        // Both sides require `obce` attributes to calculate ids of methods and to replace types
+        let ink = Self::ink(trait_item.clone(), &trait_config)?;
+        let substrate = Self::substrate(trait_item.clone(), &trait_config)?;
+
         // Remove all `obce` attributes from trait's methods
-        trait_item.items.iter_mut().for_each(|item| {
-            if let TraitItem::Method(method) = item {
-                method.attrs = method
-                    .attrs
-                    .clone()
-                    .into_iter()
-                    .filter(|attr| !attr.path.is_ident("obce"))
-                    .collect();
-            }
-        });
+        remove_obce_attributes(&mut trait_item);
 
-        let id = config.id;
-        let substrate = Self::substrate(trait_item.clone())?;
-        let ink = Self::ink(trait_item.clone(), &config)?;
+        let id = trait_config.id;
         let (impls, types, where_clause) = trait_item.generics.split_for_impl();
 
-        let code = quote! {
+        let descriptions = quote! {
             impl #impls ::obce::codegen::ExtensionDescription for dyn #trait_name #types #where_clause {
                 const ID: ::core::primitive::u16 = #id;
+                const METHODS: &'static [::obce::codegen::MethodMetadata] = &[
+                    #(#methods_metadata),*
+                ];
             }
 
             #(#method_descriptions)*
+        };
+
+        if trait_config.both {
+            // Each side has its own trait, so descriptions are implemented for both of them.
+            let vis = &trait_item.vis;
+            let ink = quote! {
+                #vis mod ink {
+                    use super::*;
+
+                    #descriptions
+
+                    #ink
+                }
+            };
+            let substrate = quote! {
+                #vis mod substrate {
+                    use super::*;
+
+                    #descriptions
+
+                    #substrate
+                }
+            };
+            let ink = match trait_config.ink_cfg {
+                Some(cfg) => with_cfg(cfg, ink)?,
+                None => ink,
+            };
+            let substrate = match trait_config.substrate_cfg {
+                Some(cfg) => with_cfg(cfg, substrate)?,
+                None => substrate,
+            };
+
+            return Ok(quote! {
+                #lock
+
+                #substrate
+
+                #ink
+            })
+        }
+
+        let ink_cfg = trait_config.ink_cfg.unwrap_or_else(|| quote! { feature = "ink" });
+        let substrate_cfg = trait_config
+            .substrate_cfg
+            .unwrap_or_else(|| quote! { feature = "substrate" });
+        let ink = with_cfg(ink_cfg, ink)?;
+        let substrate = with_cfg(substrate_cfg, substrate)?;
+
+        Ok(quote! {
+            #lock
+
+            #descriptions
 
-            #[cfg(feature = "substrate")]
             #substrate
 
-            #[cfg(feature = "ink")]
             #ink
-        };
-        Ok(code)
+        })
     }
 
-    fn substrate(trait_item: ItemTrait) -> Result<TokenStream, Error> {
+    fn substrate(mut trait_item: ItemTrait, trait_config: &Config) -> Result<TokenStream, Error> {
+        let mut hidden = vec![];
+        for item in trait_item.items.iter_mut() {
+            if let TraitItem::Method(method) = item {
+                let declared = method.clone();
+                let mapped = map_types(method, Side::Substrate)?;
+                hidden.push(validation_method(&declared, method)?);
+                hidden.push(same_shape_method(method, &mapped));
+                let status = StatusCode::new(method, &MethodConfig::new(method)?, trait_config)?;
+                hidden.push(output_method(method, status.as_ref()));
+            }
+        }
+
+        remove_obce_attributes(&mut trait_item);
+        trait_item.items.extend(hidden);
+        let allow_list = allow_list(&trait_item, trait_config.allow.as_ref());
+
         Ok(quote! {
             #trait_item
+
+            #allow_list
         })
     }
 
     fn ink(mut trait_item: ItemTrait, trait_config: &Config) -> Result<TokenStream, Error> {
         let ext_id = (trait_config.id as u32) << 16;
+        let mut batch_methods = vec![];
+        let mut ref_methods = vec![];
+        let mut dyn_ref_methods = vec![];
+        let mut try_methods = vec![];
 
         for item in trait_item.items.iter_mut() {
             if let TraitItem::Method(method) = item {
-                let config = MethodConfig::new(&method)?;
+                map_types(method, Side::Ink)?;
+                let config = MethodConfig::new(method)?;
+                let status = StatusCode::new(method, &config, trait_config)?;
                 let input = config.input;
                 let output = config.output;
                 let func_id = config.id;
 
+                // View methods don't modify the state, so they don't require the mutable receiver.
+                if config.view {
+                    if let Some(syn::FnArg::Receiver(receiver)) = method.sig.inputs.first_mut() {
+                        receiver.mutability = None;
+                    }
+                }
+
                 let input_bound = parse2(quote! {
                     #input : ::scale::Encode
                 })
                 .map_err(|error| format_err_spanned!(method, "can't parse autogenerated encode bound {}", error))?;
+                // Errors reported by the status code are not encoded into the output.
+                let decoded = match &status {
+                    Some(StatusCode {
+                        ok,
+                        output_result: false,
+                        ..
+                    }) => quote! { #ok },
+                    _ => output.clone(),
+                };
                 let output_bound = parse2(quote! {
-                    #output : ::scale::Decode
+                    #decoded : ::scale::Decode
                 })
                 .map_err(|error| format_err_spanned!(method, "can't parse autogenerated decode bound {}", error))?;
 
@@ -234,42 +558,802 @@ impl ChainExtensionDefinition {
                 }
 
                 let id = ext_id | (func_id as u32);
-                let input_bindings = method.sig.inputs.iter().filter_map(|input| {
-                    if let syn::FnArg::Typed(pat) = input {
-                        Some(pat.pat.clone())
-                    } else {
-                        None
-                    }
-                });
+                let id = quote!(#id);
+                let call = Self::ink_call(method, &id, &input, &output, status.as_ref());
+                let try_call = Self::ink_try_call(method, &id, &input, &output, status.as_ref());
                 method.default = Some(
                     parse2(quote! {
                         {
-                            ::obce::ink::ink_env::chain_extension::ChainExtensionMethod::build(#id)
-                                .input::<#input>()
-                                .output::<#output>()
-                                .ignore_error_code()
-                                .call(&( #(#input_bindings),* ))
+                            #call
                         }
                     })
                     .map_err(|error| format_err_spanned!(method, "can't parse autogenerated default {}", error))?,
                 );
+                try_methods.push(Self::ink_try_method(method, &try_call, &output)?);
+                // The batch call returns only encoded outputs, so it can't report status codes.
+                if status.is_none() {
+                    batch_methods.push(Self::ink_batch_method(method, func_id, &input, &output));
+                }
+
+                ref_methods.push(Self::ink_ref_method(method, &call, &output));
+                ref_methods.push(Self::ink_ref_try_method(method, &try_call, &output));
+
+                // The dynamic caller type uses the extension id that was chosen at runtime.
+                let func_id = func_id as u32;
+                let dyn_id = quote!(((self.extension_id as u32) << 16) | #func_id);
+                let call = Self::ink_call(method, &dyn_id, &input, &output, status.as_ref());
+                let try_call = Self::ink_try_call(method, &dyn_id, &input, &output, status.as_ref());
+                dyn_ref_methods.push(Self::ink_ref_method(method, &call, &output));
+                dyn_ref_methods.push(Self::ink_ref_try_method(method, &try_call, &output));
             }
         }
+        trait_item.items.extend(try_methods.into_iter().map(TraitItem::Method));
+
+        remove_obce_attributes(&mut trait_item);
+        let batch = Self::ink_batch(&trait_item, batch_methods);
+        let reference = Self::ink_ref(&trait_item, ref_methods, dyn_ref_methods);
 
         Ok(quote! {
             #trait_item
+
+            #batch
+
+            #reference
         })
     }
+
+    fn ink_call(
+        method: &TraitItemMethod,
+        id: &TokenStream,
+        input: &TokenStream,
+        output: &TokenStream,
+        status: Option<&StatusCode>,
+    ) -> TokenStream {
+        let input_bindings = method.sig.inputs.iter().filter_map(|input| {
+            if let syn::FnArg::Typed(pat) = input {
+                Some(pat.pat.clone())
+            } else {
+                None
+            }
+        });
+
+        if let Some(StatusCode {
+            error_code,
+            output_result: true,
+            ..
+        }) = status
+        {
+            quote! {
+                match ::obce::ink::ink_env::chain_extension::ChainExtensionMethod::build(#id)
+                    .input::<#input>()
+                    .output::<#output>()
+                    .handle_error_code::<#error_code>()
+                    .call(&( #(#input_bindings),* ))
+                {
+                    ::core::result::Result::Ok(output) => output,
+                    ::core::result::Result::Err(error_code) => {
+                        ::core::result::Result::Err(::core::convert::From::from(error_code))
+                    }
+                }
+            }
+        } else if let Some(StatusCode { ok, error_code, .. }) = status {
+            quote! {
+                ::obce::ink::ink_env::chain_extension::ChainExtensionMethod::build(#id)
+                    .input::<#input>()
+                    .output::<#ok>()
+                    .handle_error_code::<#error_code>()
+                    .call(&( #(#input_bindings),* ))
+                    .map_err(::core::convert::From::from)
+            }
+        } else {
+            quote! {
+                ::obce::ink::ink_env::chain_extension::ChainExtensionMethod::build(#id)
+                    .input::<#input>()
+                    .output::<#output>()
+                    .ignore_error_code()
+                    .call(&( #(#input_bindings),* ))
+            }
+        }
+    }
+
+    fn ink_try_call(
+        method: &TraitItemMethod,
+        id: &TokenStream,
+        input: &TokenStream,
+        output: &TokenStream,
+        status: Option<&StatusCode>,
+    ) -> TokenStream {
+        let input_bindings = method.sig.inputs.iter().filter_map(|input| {
+            if let syn::FnArg::Typed(pat) = input {
+                Some(pat.pat.clone())
+            } else {
+                None
+            }
+        });
+
+        if let Some(StatusCode {
+            ok,
+            error_code,
+            output_result,
+        }) = status
+        {
+            let decoded = if *output_result {
+                quote! { output.decode_as::<#output>() }
+            } else {
+                quote! { output.decode_as::<#ok>().map(::core::result::Result::Ok) }
+            };
+            quote! {
+                match ::obce::ink::ink_env::chain_extension::ChainExtensionMethod::build(#id)
+                    .input::<#input>()
+                    .output::<::obce::ink::EncodedOutput>()
+                    .handle_error_code::<#error_code>()
+                    .call(&( #(#input_bindings),* ))
+                {
+                    ::core::result::Result::Ok(output) => #decoded,
+                    ::core::result::Result::Err(error_code) => ::core::result::Result::Ok(
+                        ::core::result::Result::Err(::core::convert::From::from(error_code)),
+                    ),
+                }
+            }
+        } else {
+            quote! {
+                ::obce::ink::ink_env::chain_extension::ChainExtensionMethod::build(#id)
+                    .input::<#input>()
+                    .output::<::obce::ink::EncodedOutput>()
+                    .ignore_error_code()
+                    .call(&( #(#input_bindings),* ))
+                    .decode_as::<#output>()
+            }
+        }
+    }
+
+    fn ink_try_method(
+        method: &TraitItemMethod,
+        try_call: &TokenStream,
+        output: &TokenStream,
+    ) -> Result<TraitItemMethod, Error> {
+        let mut try_method = method.clone();
+        try_method.sig.ident = format_ident!("try_{}", method.sig.ident);
+        try_method.sig.output = parse2(quote! {
+            -> ::core::result::Result<#output, ::obce::ink::ink_env::Error>
+        })
+        .map_err(|error| format_err_spanned!(method, "can't parse autogenerated output {}", error))?;
+        try_method.default = Some(
+            parse2(quote! {
+                {
+                    #try_call
+                }
+            })
+            .map_err(|error| format_err_spanned!(method, "can't parse autogenerated default {}", error))?,
+        );
+        Ok(try_method)
+    }
+
+    fn ink_ref_try_method(method: &TraitItemMethod, try_call: &TokenStream, output: &TokenStream) -> TokenStream {
+        let method_name = format_ident!("try_{}", method.sig.ident);
+        let inputs = method.sig.inputs.iter().filter_map(|input| {
+            if let syn::FnArg::Typed(pat) = input {
+                Some(pat)
+            } else {
+                None
+            }
+        });
+        let where_clause = &method.sig.generics.where_clause;
+
+        quote! {
+            pub fn #method_name(&self #(, #inputs)*) -> ::core::result::Result<#output, ::obce::ink::ink_env::Error>
+                #where_clause
+            {
+                #try_call
+            }
+        }
+    }
+
+    fn ink_ref_method(method: &TraitItemMethod, call: &TokenStream, output: &TokenStream) -> TokenStream {
+        let method_name = &method.sig.ident;
+        let inputs = method.sig.inputs.iter().filter_map(|input| {
+            if let syn::FnArg::Typed(pat) = input {
+                Some(pat)
+            } else {
+                None
+            }
+        });
+        let where_clause = &method.sig.generics.where_clause;
+
+        quote! {
+            pub fn #method_name(&self #(, #inputs)*) -> #output
+                #where_clause
+            {
+                #call
+            }
+        }
+    }
+
+    fn ink_ref(
+        trait_item: &ItemTrait,
+        ref_methods: Vec<TokenStream>,
+        dyn_ref_methods: Vec<TokenStream>,
+    ) -> TokenStream {
+        let trait_name = &trait_item.ident;
+        let vis = &trait_item.vis;
+        let ref_name = format_ident!("{}Ref", trait_name);
+        let dyn_ref_name = format_ident!("{}DynRef", trait_name);
+        let generics = &trait_item.generics;
+        let (impls, types, where_clause) = trait_item.generics.split_for_impl();
+
+        quote! {
+            /// Zero-sized caller of the chain extension's methods.
+            ///
+            /// It uses the extension id from the definition. Use `with_extension_id`
+            /// to call the chain extension that is registered under another id.
+            #vis struct #ref_name #generics #where_clause {
+                _phantom: ::core::marker::PhantomData<fn() -> *const dyn #trait_name #types>,
+            }
+
+            impl #impls #ref_name #types #where_clause {
+                pub const fn new() -> Self {
+                    Self {
+                        _phantom: ::core::marker::PhantomData,
+                    }
+                }
+
+                pub const fn with_extension_id(extension_id: u16) -> #dyn_ref_name #types {
+                    #dyn_ref_name::with_extension_id(extension_id)
+                }
+
+                #(#ref_methods)*
+            }
+
+            impl #impls ::core::default::Default for #ref_name #types #where_clause {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl #impls ::core::clone::Clone for #ref_name #types #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl #impls ::core::marker::Copy for #ref_name #types #where_clause {}
+
+            /// Caller of the chain extension's methods with the extension id chosen at runtime.
+            ///
+            /// By default, it uses the extension id from the definition.
+            #vis struct #dyn_ref_name #generics #where_clause {
+                extension_id: u16,
+                _phantom: ::core::marker::PhantomData<fn() -> *const dyn #trait_name #types>,
+            }
+
+            impl #impls #dyn_ref_name #types #where_clause {
+                pub const fn new() -> Self {
+                    Self::with_extension_id(<dyn #trait_name #types as ::obce::codegen::ExtensionDescription>::ID)
+                }
+
+                pub const fn with_extension_id(extension_id: u16) -> Self {
+                    Self {
+                        extension_id,
+                        _phantom: ::core::marker::PhantomData,
+                    }
+                }
+
+                pub const fn extension_id(&self) -> u16 {
+                    self.extension_id
+                }
+
+                #(#dyn_ref_methods)*
+            }
+
+            impl #impls ::core::default::Default for #dyn_ref_name #types #where_clause {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+
+            impl #impls ::core::clone::Clone for #dyn_ref_name #types #where_clause {
+                fn clone(&self) -> Self {
+                    *self
+                }
+            }
+
+            impl #impls ::core::marker::Copy for #dyn_ref_name #types #where_clause {}
+        }
+    }
+
+    fn ink_batch_method(
+        method: &TraitItemMethod,
+        func_id: u16,
+        input: &TokenStream,
+        output: &TokenStream,
+    ) -> TokenStream {
+        let method_name = &method.sig.ident;
+        let inputs = method.sig.inputs.iter().filter_map(|input| {
+            if let syn::FnArg::Typed(pat) = input {
+                Some(pat)
+            } else {
+                None
+            }
+        });
+        let input_bindings = inputs.clone().map(|pat| &pat.pat);
+        let where_clause = &method.sig.generics.where_clause;
+
+        quote! {
+            pub fn #method_name(&mut self #(, #inputs)*) -> ::obce::ink::BatchHandle<#output>
+                #where_clause
+            {
+                self.builder.push::<#input, #output>(#func_id, &( #(#input_bindings),* ))
+            }
+        }
+    }
+
+    fn ink_batch(trait_item: &ItemTrait, batch_methods: Vec<TokenStream>) -> TokenStream {
+        let trait_name = &trait_item.ident;
+        let vis = &trait_item.vis;
+        let batch_name = format_ident!("{}Batch", trait_name);
+        let generics = &trait_item.generics;
+        let (impls, types, where_clause) = trait_item.generics.split_for_impl();
+
+        quote! {
+            /// Builder of the batch call that executes several methods in one call.
+            #vis struct #batch_name #generics #where_clause {
+                builder: ::obce::ink::BatchBuilder,
+                _phantom: ::core::marker::PhantomData<fn() -> *const dyn #trait_name #types>,
+            }
+
+            impl #impls #batch_name #types #where_clause {
+                pub fn new() -> Self {
+                    Self::with_extension_id(<dyn #trait_name #types as ::obce::codegen::ExtensionDescription>::ID)
+                }
+
+                pub fn with_extension_id(extension_id: u16) -> Self {
+                    Self {
+                        builder: ::obce::ink::BatchBuilder::new(extension_id),
+                        _phantom: ::core::marker::PhantomData,
+                    }
+                }
+
+                #(#batch_methods)*
+
+                pub fn call(self) -> ::obce::ink::BatchOutput {
+                    self.builder.call()
+                }
+            }
+
+            impl #impls ::core::default::Default for #batch_name #types #where_clause {
+                fn default() -> Self {
+                    Self::new()
+                }
+            }
+        }
+    }
+}
+
+/// Puts `#[cfg(#cfg)]` on each item of the `items`.
+fn with_cfg(cfg: TokenStream, items: TokenStream) -> Result<TokenStream, Error> {
+    let mut file: File = parse2(items)?;
+    for item in file.items.iter_mut() {
+        let attr: Attribute = parse_quote! { #[cfg(#cfg)] };
+        match item {
+            Item::Trait(item) => item.attrs.insert(0, attr),
+            Item::Struct(item) => item.attrs.insert(0, attr),
+            Item::Impl(item) => item.attrs.insert(0, attr),
+            Item::Fn(item) => item.attrs.insert(0, attr),
+            Item::Mod(item) => item.attrs.insert(0, attr),
+            item => return Err(format_err_spanned!(item, "unexpected autogenerated item")),
+        }
+    }
+    Ok(quote! { #file })
+}
+
+fn remove_obce_attributes(trait_item: &mut ItemTrait) {
+    trait_item.items.iter_mut().for_each(|item| {
+        if let TraitItem::Method(method) = item {
+            method.attrs = method
+                .attrs
+                .clone()
+                .into_iter()
+                .filter(|attr| !attr.path.is_ident("obce"))
+                .collect();
+            method.sig.inputs.iter_mut().for_each(|input| {
+                if let FnArg::Typed(pat) = input {
+                    pat.attrs.retain(|attr| !attr.path.is_ident("obce"));
+                }
+            });
+        }
+    });
 }
 
-fn extract_attributes(attrs: AttributeArgs) -> Result<Option<u16>, Error> {
-    let mut id = None;
+/// The side of the chain extension where the trait is used.
+#[derive(Clone, Copy)]
+enum Side {
+    Ink,
+    Substrate,
+}
+
+impl Side {
+    fn select(self, ink_type: Option<Type>, substrate_type: Option<Type>) -> Option<Type> {
+        match self {
+            Side::Ink => ink_type,
+            Side::Substrate => substrate_type,
+        }
+    }
+}
+
+/// Replaces types of arguments and the return type of the method with the types of the `side`
+/// specified by `ink_type` and `substrate_type`.
+///
+/// Returns pairs of the declared and the replaced types, so their shapes can be compared.
+fn map_types(method: &mut TraitItemMethod, side: Side) -> Result<Vec<(Type, Type)>, Error> {
+    let config = MethodConfig::new(method)?;
+    let mut mapped = vec![];
+
+    for input in method.sig.inputs.iter_mut() {
+        if let FnArg::Typed(pat) = input {
+            let attrs = argument_attributes(pat)?;
+            pat.attrs.retain(|attr| !attr.path.is_ident("obce"));
+
+            if let Some(ty) = side.select(attrs.ink_type, attrs.substrate_type) {
+                mapped.push(((*pat.ty).clone(), ty.clone()));
+                *pat.ty = ty;
+            }
+        }
+    }
+
+    if let Some(ty) = side.select(config.ink_output, config.substrate_output) {
+        match &mut method.sig.output {
+            ReturnType::Type(_, output) => {
+                mapped.push(((**output).clone(), ty.clone()));
+                **output = ty;
+            }
+            ReturnType::Default => {
+                mapped.push((parse_quote! { () }, ty.clone()));
+                method.sig.output = parse_quote! { -> #ty };
+            }
+        }
+    }
+
+    Ok(mapped)
+}
+
+/// Generates the hidden method of the substrate trait that compares `scale_info` shapes of
+/// the declared and the replaced types of the method.
+///
+/// Types may depend on generics of the trait, like `T::AccountId`, so shapes are compared by
+/// the dispatcher of the implementation, where all types are known. Both types should implement
+/// `TypeInfo`, that is checked by the compiler where the types are known.
+fn same_shape_method(method: &TraitItemMethod, mapped: &[(Type, Type)]) -> TraitItem {
+    let ident = format_ident!("__obce_same_shape_{}", method.sig.ident);
+    let mut generics = method.sig.generics.clone();
+    for (declared, replaced) in mapped {
+        let predicates = &mut generics.make_where_clause().predicates;
+        predicates.push(parse_quote_spanned! { declared.span() =>
+            #declared: ::obce::codegen::TypeInfo + 'static
+        });
+        predicates.push(parse_quote_spanned! { replaced.span() =>
+            #replaced: ::obce::codegen::TypeInfo + 'static
+        });
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let checks = mapped.iter().map(|(declared, replaced)| {
+        quote! { ::obce::codegen::same_shape::<#replaced, #declared>() }
+    });
+
+    parse_quote! {
+        #[doc(hidden)]
+        fn #ident #impl_generics (&self) -> ::core::primitive::bool #where_clause {
+            true #(&& #checks)*
+        }
+    }
+}
+
+/// The method reports errors by the status code of the chain extension call, as legacy
+/// `#[ink::chain_extension]` does. The output contains only the `Ok` value, or the whole
+/// `Ok` result with `output_result`, like ink!'s `returns_result = true`.
+struct StatusCode {
+    ok: Type,
+    error_code: Type,
+    output_result: bool,
+}
+
+impl StatusCode {
+    fn new(method: &TraitItemMethod, config: &MethodConfig, trait_config: &Config) -> Result<Option<Self>, Error> {
+        let error_code = match &trait_config.error_code {
+            Some(error_code) if config.handle_status != Some(false) => error_code.clone(),
+            _ if config.output_result => {
+                return Err(format_err_spanned!(
+                    method.sig,
+                    "`output_result` is supported only by methods that handle the status code"
+                ))
+            }
+            _ => return Ok(None),
+        };
+        let ok = result_ok_type(&method.sig.output).ok_or_else(|| {
+            format_err_spanned!(
+                method.sig,
+                "methods that handle the status code should return `Result<_, E>` where `E: From<{}>`, \
+                 use `#[obce(handle_status = false)]` otherwise",
+                error_code.to_token_stream(),
+            )
+        })?;
+        Ok(Some(Self {
+            ok: ok.clone(),
+            error_code,
+            output_result: config.output_result,
+        }))
+    }
+}
+
+/// Returns the type of `Ok` if the method returns `Result<T, ..>`.
+fn result_ok_type(output: &ReturnType) -> Option<&Type> {
+    result_types(output)?.next()
+}
+
+/// Returns the type of `Err` if the method returns `Result<T, E>`.
+fn result_err_type(output: &ReturnType) -> Option<&Type> {
+    result_types(output)?.nth(1)
+}
+
+/// Returns type arguments of `Result` if the method returns it.
+fn result_types(output: &ReturnType) -> Option<impl Iterator<Item = &Type>> {
+    if let ReturnType::Type(_, ty) = output {
+        if let Type::Path(path) = &**ty {
+            let segment = path.path.segments.last()?;
+            if let PathArguments::AngleBracketed(arguments) = &segment.arguments {
+                if segment.ident == "Result" {
+                    return Some(arguments.args.iter().filter_map(|argument| {
+                        match argument {
+                            GenericArgument::Type(ty) => Some(ty),
+                            _ => None,
+                        }
+                    }))
+                }
+            }
+        }
+    }
+    None
+}
+
+/// Generates the hidden method of the substrate trait that returns the value encoded into the output.
+/// If the method handles the status code, the error is returned as `Err` with the status code.
+fn output_method(method: &TraitItemMethod, status: Option<&StatusCode>) -> TraitItem {
+    let ident = format_ident!("__obce_output_{}", method.sig.ident);
+    let mut generics = method.sig.generics.clone();
+    let error = result_err_type(&method.sig.output);
+    // The error is returned as the status code, so the conversion is required at the definition.
+    if let (Some(_), Some(error)) = (status, error) {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote_spanned! { error.span() =>
+                #error: ::core::convert::Into<::core::primitive::u32>
+            });
+    }
+    let (impl_generics, _, where_clause) = generics.split_for_impl();
+    let output = match &method.sig.output {
+        ReturnType::Type(_, ty) => quote! { #ty },
+        ReturnType::Default => quote! { () },
+    };
+
+    let (value, body) = if let Some(StatusCode { ok, output_result, .. }) = status {
+        let into = match error {
+            Some(error) => {
+                quote_spanned! { error.span() => <#error as ::core::convert::Into<::core::primitive::u32>>::into }
+            }
+            None => quote! { ::core::convert::Into::into },
+        };
+        if *output_result {
+            (
+                output.clone(),
+                quote! {
+                    match output {
+                        ::core::result::Result::Ok(value) => {
+                            ::core::result::Result::Ok(::core::result::Result::Ok(value))
+                        }
+                        ::core::result::Result::Err(error) => ::core::result::Result::Err(#into(error)),
+                    }
+                },
+            )
+        } else {
+            (quote! { #ok }, quote! { output.map_err(#into) })
+        }
+    } else {
+        (output.clone(), quote! { ::core::result::Result::Ok(output) })
+    };
+
+    parse_quote! {
+        #[doc(hidden)]
+        fn #ident #impl_generics (&self, output: #output) -> ::core::result::Result<#value, ::core::primitive::u32>
+            #where_clause
+        {
+            #body
+        }
+    }
+}
+
+/// Parses `#[obce(..)]` attributes of the method's argument.
+fn argument_attributes(pat: &PatType) -> Result<Attributes, Error> {
+    let mut attributes = Attributes::default();
+    for attr in pat.attrs.iter().filter(|attr| attr.path.is_ident("obce")) {
+        let attrs = attr
+            .parse_args::<AttributeArgs>()
+            .map_err(|error| format_err_spanned!(attr, "unable decode attributes: {}", error,))?;
+        let attrs = extract_attributes(attrs)?;
+        if attrs.id.is_some()
+            || attrs.output_weight_per_byte.is_some()
+            || attrs.view
+            || !attrs.aliases.is_empty()
+            || attrs.raw_id.is_some()
+            || attrs.handle_status.is_some()
+            || attrs.output_result
+            || attrs.is_trait_only()
+        {
+            return Err(format_err_spanned!(
+                attr,
+                "only `ink_type`, `substrate_type`, `validate`, `range` and `max_len` are supported by arguments"
+            ))
+        }
+        attributes.ink_type = attrs.ink_type.or(attributes.ink_type);
+        attributes.substrate_type = attrs.substrate_type.or(attributes.substrate_type);
+        attributes.validate = attrs.validate.or(attributes.validate);
+        attributes.range = attrs.range.or(attributes.range);
+        attributes.max_len = attrs.max_len.or(attributes.max_len);
+    }
+    Ok(attributes)
+}
+
+/// Implements `ExtensionAllowList` of the substrate trait for every runtime. The dispatcher checks it
+/// before any method logic runs, so only contracts allowed by the `provider` can call the chain extension.
+fn allow_list(trait_item: &ItemTrait, provider: Option<&Path>) -> TokenStream {
+    let trait_name = &trait_item.ident;
+    let (_, types, _) = trait_item.generics.split_for_impl();
+    let mut generics = trait_item.generics.clone();
+    generics
+        .params
+        .push(parse_quote! { __ObceRuntime: ::obce::substrate::pallet_contracts::Config });
+    let namespace = quote! { ::obce::substrate::pallet_contracts::chain_extension:: };
+
+    let ensure_allowed = if let Some(provider) = provider {
+        generics
+            .make_where_clause()
+            .predicates
+            .push(parse_quote! { #provider: ::obce::substrate::AllowListProvider<__ObceRuntime> });
+        quote! {
+            fn ensure_allowed<E>(
+                env: &mut #namespace Environment<E, #namespace BufInBufOutState>,
+            ) -> ::core::result::Result<(), ::obce::substrate::sp_runtime::DispatchError>
+            where
+                E: #namespace Ext<T = __ObceRuntime>,
+                <E::T as ::obce::substrate::frame_system::Config>::AccountId: #namespace UncheckedFrom<
+                    <E::T as ::obce::substrate::frame_system::Config>::Hash
+                > + ::core::convert::AsRef<[::core::primitive::u8]>,
+            {
+                ::obce::substrate::ensure_allowed::<#provider, E>(env)
+            }
+        }
+    } else {
+        quote! {}
+    };
+    let (impls, _, where_clause) = generics.split_for_impl();
+
+    quote! {
+        impl #impls ::obce::substrate::ExtensionAllowList<__ObceRuntime> for dyn #trait_name #types #where_clause {
+            #ensure_allowed
+        }
+    }
+}
+
+/// Generates the hidden method of the substrate trait that checks arguments of the method
+/// according to `validate`, `range` and `max_len` attributes. The dispatcher calls it after
+/// decoding of the input and returns `Some` output to the contract instead of calling the method.
+fn validation_method(declared: &TraitItemMethod, mapped: &TraitItemMethod) -> Result<TraitItem, Error> {
+    let method_name = mapped.sig.ident.to_string();
+    let ident = format_ident!("__obce_validate_{}", mapped.sig.ident);
+    let (impl_generics, _, where_clause) = mapped.sig.generics.split_for_impl();
+    let output = match &mapped.sig.output {
+        ReturnType::Type(_, ty) => quote! { #ty },
+        ReturnType::Default => quote! { () },
+    };
+
+    let mut inputs = vec![];
+    let mut checks = vec![];
+    for (i, (declared, mapped)) in declared.sig.inputs.iter().zip(mapped.sig.inputs.iter()).enumerate() {
+        if let (FnArg::Typed(declared), FnArg::Typed(mapped)) = (declared, mapped) {
+            let binding = format_ident!("__obce_arg_{}", i);
+            let ty = &mapped.ty;
+            inputs.push(quote! { #binding: &#ty });
+
+            let attrs = argument_attributes(declared)?;
+            let argument = declared.pat.to_token_stream().to_string();
+            let mut conditions = vec![];
+            if let Some(validate) = attrs.validate {
+                conditions.push(quote! { #validate(#binding) });
+            }
+            if let Some(range) = attrs.range {
+                conditions.push(quote! { (#range).contains(#binding) });
+            }
+            if let Some(max_len) = attrs.max_len {
+                conditions.push(quote! { #binding.len() <= #max_len });
+            }
+            if !conditions.is_empty() {
+                checks.push(quote! {
+                    if !(#(#conditions)&&*) {
+                        return ::core::option::Option::Some(::core::result::Result::Err(
+                            ::core::convert::From::from(::obce::codegen::InvalidArgument {
+                                method: #method_name,
+                                argument: #argument,
+                            }),
+                        ))
+                    }
+                });
+            }
+        }
+    }
+
+    // The invalid argument is a non-critical error, so it is returned as `Err` of the output.
+    if !checks.is_empty() && result_ok_type(&mapped.sig.output).is_none() {
+        return Err(format_err_spanned!(
+            mapped.sig,
+            "methods with validated arguments should return `Result<_, E>` where `E: From<obce::codegen::InvalidArgument>`"
+        ))
+    }
+
+    Ok(parse_quote! {
+        #[doc(hidden)]
+        fn #ident #impl_generics (&self, #(#inputs),*) -> ::core::option::Option<#output> #where_clause {
+            #(#checks)*
+            ::core::option::Option::None
+        }
+    })
+}
+
+#[derive(Default)]
+struct Attributes {
+    id: Option<u16>,
+    output_weight_per_byte: Option<u64>,
+    view: bool,
+    ink_type: Option<Type>,
+    substrate_type: Option<Type>,
+    ink_cfg: Option<TokenStream>,
+    substrate_cfg: Option<TokenStream>,
+    both: bool,
+    aliases: Vec<u16>,
+    lock: Option<String>,
+    validate: Option<Path>,
+    range: Option<Expr>,
+    max_len: Option<LitInt>,
+    raw_id: Option<u32>,
+    legacy: bool,
+    error_code: Option<Type>,
+    handle_status: Option<bool>,
+    output_result: bool,
+    allow: Option<Path>,
+}
+
+impl Attributes {
+    fn is_trait_only(&self) -> bool {
+        self.ink_cfg.is_some()
+            || self.substrate_cfg.is_some()
+            || self.both
+            || self.lock.is_some()
+            || self.legacy
+            || self.error_code.is_some()
+            || self.allow.is_some()
+    }
+
+    fn is_validation(&self) -> bool {
+        self.validate.is_some() || self.range.is_some() || self.max_len.is_some()
+    }
+}
+
+fn extract_attributes(attrs: AttributeArgs) -> Result<Attributes, Error> {
+    let mut attributes = Attributes::default();
     for attr in attrs.iter() {
         match attr {
             NestedMeta::Meta(Meta::NameValue(value)) => {
                 if value.path.is_ident("id") {
                     if let Lit::Int(lit_id) = &value.lit {
-                        id = Some(lit_id.base10_parse::<u16>().map_err(|error| {
+                        attributes.id = Some(lit_id.base10_parse::<u16>().map_err(|error| {
                             format_err_spanned!(
                                 value.lit,
                                 "id out of range. id must be a valid `u16` integer: {}",
@@ -277,10 +1361,141 @@ fn extract_attributes(attrs: AttributeArgs) -> Result<Option<u16>, Error> {
                             )
                         })?);
                     } else if let Lit::Str(lit_id) = &value.lit {
-                        id = Some(into_u16(lit_id.value()));
+                        attributes.id = Some(into_u16(lit_id.value()));
                     } else {
                         Err(format_err_spanned!(value, "id should be integer or string"))?;
                     }
+                } else if value.path.is_ident("output_weight_per_byte") {
+                    if let Lit::Int(lit_weight) = &value.lit {
+                        attributes.output_weight_per_byte =
+                            Some(lit_weight.base10_parse::<u64>().map_err(|error| {
+                                format_err_spanned!(
+                                    value.lit,
+                                    "weight out of range. weight must be a valid `u64` integer: {}",
+                                    error,
+                                )
+                            })?);
+                    } else {
+                        Err(format_err_spanned!(value, "weight should be integer"))?;
+                    }
+                } else if value.path.is_ident("ink_type") || value.path.is_ident("substrate_type") {
+                    let ty = if let Lit::Str(lit_ty) = &value.lit {
+                        lit_ty.parse::<Type>()?
+                    } else {
+                        return Err(format_err_spanned!(value, "type should be a path or a string"))
+                    };
+                    if value.path.is_ident("ink_type") {
+                        attributes.ink_type = Some(ty);
+                    } else {
+                        attributes.substrate_type = Some(ty);
+                    }
+                } else if value.path.is_ident("error_code") {
+                    if let Lit::Str(lit_ty) = &value.lit {
+                        attributes.error_code = Some(lit_ty.parse::<Type>()?);
+                    } else {
+                        Err(format_err_spanned!(value, "error_code should be a path or a string"))?;
+                    }
+                } else if value.path.is_ident("handle_status") {
+                    if let Lit::Bool(lit_bool) = &value.lit {
+                        attributes.handle_status = Some(lit_bool.value);
+                    } else {
+                        Err(format_err_spanned!(value, "handle_status should be boolean"))?;
+                    }
+                } else if value.path.is_ident("raw_id") {
+                    if let Lit::Int(lit_id) = &value.lit {
+                        attributes.raw_id = Some(lit_id.base10_parse::<u32>().map_err(|error| {
+                            format_err_spanned!(
+                                value.lit,
+                                "raw_id out of range. raw_id must be a valid `u32` integer: {}",
+                                error,
+                            )
+                        })?);
+                    } else {
+                        Err(format_err_spanned!(value, "raw_id should be integer"))?;
+                    }
+                } else if value.path.is_ident("validate") {
+                    if let Lit::Str(lit_path) = &value.lit {
+                        attributes.validate = Some(lit_path.parse::<Path>()?);
+                    } else {
+                        Err(format_err_spanned!(value, "validate should be a path to the function"))?;
+                    }
+                } else if value.path.is_ident("allow") {
+                    if let Lit::Str(lit_path) = &value.lit {
+                        attributes.allow = Some(lit_path.parse::<Path>()?);
+                    } else {
+                        Err(format_err_spanned!(value, "allow list provider should be a path"))?;
+                    }
+                } else if value.path.is_ident("max_len") {
+                    if let Lit::Int(lit_len) = &value.lit {
+                        lit_len.base10_parse::<usize>().map_err(|error| {
+                            format_err_spanned!(
+                                value.lit,
+                                "max_len out of range. max_len must be a valid `usize` integer: {}",
+                                error,
+                            )
+                        })?;
+                        attributes.max_len = Some(lit_len.clone());
+                    } else {
+                        Err(format_err_spanned!(value, "max_len should be integer"))?;
+                    }
+                } else if value.path.is_ident("lock") {
+                    if let Lit::Str(lit_path) = &value.lit {
+                        attributes.lock = Some(lit_path.value());
+                    } else {
+                        Err(format_err_spanned!(value, "lock should be a path to the file"))?;
+                    }
+                } else if value.path.is_ident("ink_cfg") || value.path.is_ident("substrate_cfg") {
+                    let cfg = if let Lit::Str(lit_cfg) = &value.lit {
+                        let predicate = lit_cfg.parse::<Meta>()?;
+                        quote! { #predicate }
+                    } else {
+                        return Err(format_err_spanned!(value, "cfg should be a predicate or a string"))
+                    };
+                    if value.path.is_ident("ink_cfg") {
+                        attributes.ink_cfg = Some(cfg);
+                    } else {
+                        attributes.substrate_cfg = Some(cfg);
+                    }
+                } else {
+                    Err(format_err_spanned!(value, "unexpected attribute"))?;
+                }
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("view") => {
+                attributes.view = true;
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("both") => {
+                attributes.both = true;
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("legacy") => {
+                attributes.legacy = true;
+            }
+            NestedMeta::Meta(Meta::Path(path)) if path.is_ident("output_result") => {
+                attributes.output_result = true;
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("range") => {
+                if let Some(NestedMeta::Lit(Lit::Str(lit_range))) = list.nested.first() {
+                    attributes.range = Some(lit_range.parse::<Expr>()?);
+                } else {
+                    Err(format_err_spanned!(list, "range should be a range expression"))?;
+                }
+            }
+            NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("aliases") => {
+                for alias in list.nested.iter() {
+                    match alias {
+                        NestedMeta::Lit(Lit::Int(lit_id)) => {
+                            attributes.aliases.push(lit_id.base10_parse::<u16>().map_err(|error| {
+                                format_err_spanned!(
+                                    lit_id,
+                                    "alias out of range. alias must be a valid `u16` integer: {}",
+                                    error,
+                                )
+                            })?);
+                        }
+                        NestedMeta::Lit(Lit::Str(lit_name)) => {
+                            attributes.aliases.push(into_u16(lit_name.value()));
+                        }
+                        _ => Err(format_err_spanned!(alias, "alias should be integer or string"))?,
+                    }
                 }
             }
             _ => {
@@ -289,5 +1504,5 @@ fn extract_attributes(attrs: AttributeArgs) -> Result<Option<u16>, Error> {
         }
     }
 
-    Ok(id)
+    Ok(attributes)
 }
@@ -21,6 +21,7 @@
 
 use crate::{
     format_err_spanned,
+    types::AttributeArgs,
     utils::into_u32,
 };
 use proc_macro2::TokenStream;
@@ -31,42 +32,264 @@ use quote::{
 };
 use syn::{
     parse2,
+    parse_quote,
     punctuated::Punctuated,
     Error,
     FnArg,
     GenericArgument,
     Generics,
     ImplItem,
+    ImplItemMethod,
     ItemImpl,
+    Lit,
+    Meta,
+    MetaList,
+    NestedMeta,
+    Path,
     PathArguments,
     Token,
     Type,
 };
 
+struct Config {
+    // The target of the logs, if tracing is enabled
+    trace: Option<String>,
+    // All methods are transactional
+    transactional: bool,
+    // All methods are guarded against reentrancy
+    non_reentrant: bool,
+    // Disabled methods are rejected via `obce::substrate::pallet`
+    kill_switch: bool,
+    // The provider of contracts allowed to call the chain extension
+    allow: Option<Path>,
+    // `ExtensionHooks` of the chain extension are called around each method
+    hooks: bool,
+    // `Layer`s around each method, the first one is the outermost
+    layers: Vec<Path>,
+    // The function that tells whether the chain extension is enabled
+    enabled: Option<Path>,
+    // Only the dispatcher is generated, the chain extension is implemented by another impl
+    dispatch_only: bool,
+    // Other traits implemented by the chain extension that are dispatched by this impl
+    merge: Vec<Path>,
+}
+
+impl Config {
+    fn new(impl_item: &ItemImpl, attrs: TokenStream) -> Result<Self, Error> {
+        let mut config = Self {
+            trace: None,
+            transactional: false,
+            non_reentrant: false,
+            kill_switch: false,
+            allow: None,
+            hooks: false,
+            layers: vec![],
+            enabled: None,
+            dispatch_only: false,
+            merge: vec![],
+        };
+        config.parse_attributes(impl_item, attrs)?;
+        Ok(config)
+    }
+
+    fn parse_attributes(&mut self, impl_item: &ItemImpl, attrs: TokenStream) -> Result<(), Error> {
+        let attrs = parse2::<AttributeArgs>(attrs.clone())
+            .map_err(|error| format_err_spanned!(attrs, "unable decode attributes: {}", error,))?;
+
+        for attr in attrs.iter() {
+            match attr {
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("trace") => {
+                    let trait_name = impl_item
+                        .trait_
+                        .as_ref()
+                        .and_then(|(_, path, _)| path.segments.last())
+                        .map(|segment| segment.ident.to_string())
+                        .unwrap_or_default();
+                    self.trace = Some(format!("obce::{}", trait_name));
+                }
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("trace") => {
+                    if let Lit::Str(target) = &value.lit {
+                        self.trace = Some(target.value());
+                    } else {
+                        Err(format_err_spanned!(value, "trace target should be a string"))?;
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("transactional") => {
+                    self.transactional = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("non_reentrant") => {
+                    self.non_reentrant = true;
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("kill_switch") => {
+                    self.kill_switch = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("allow") => {
+                    if let Lit::Str(provider) = &value.lit {
+                        self.allow = Some(provider.parse()?);
+                    } else {
+                        Err(format_err_spanned!(value, "allow list provider should be a path"))?;
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("hooks") => {
+                    self.hooks = true;
+                }
+                NestedMeta::Meta(Meta::NameValue(value)) if value.path.is_ident("enabled") => {
+                    if let Lit::Str(function) = &value.lit {
+                        self.enabled = Some(function.parse()?);
+                    } else {
+                        Err(format_err_spanned!(value, "enabled should be a path to the function"))?;
+                    }
+                }
+                NestedMeta::Meta(Meta::Path(path)) if path.is_ident("dispatch_only") => {
+                    self.dispatch_only = true;
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("merge") => {
+                    self.merge.extend(parse_paths(list, "merged trait should be a path")?);
+                }
+                NestedMeta::Meta(Meta::List(list)) if list.path.is_ident("layers") => {
+                    self.layers.extend(parse_paths(list, "layer should be a path")?);
+                }
+                _ => {
+                    Err(format_err_spanned!(attr, "unexpected attribute"))?;
+                }
+            }
+        }
+
+        if self.dispatch_only && !self.merge.is_empty() {
+            return Err(format_err_spanned!(
+                impl_item.self_ty,
+                "`dispatch_only` implementation can't merge other traits"
+            ))
+        }
+        if self.dispatch_only && self.enabled.is_some() {
+            return Err(format_err_spanned!(
+                impl_item.self_ty,
+                "`enabled` should be set on the implementation that merges other traits"
+            ))
+        }
+
+        Ok(())
+    }
+}
+
+fn parse_paths(list: &MetaList, error: &str) -> Result<Vec<Path>, Error> {
+    let mut paths = vec![];
+    for nested in list.nested.iter() {
+        match nested {
+            NestedMeta::Meta(Meta::Path(path)) => paths.push(path.clone()),
+            // Paths with generic arguments are not metas, so they come as a string
+            NestedMeta::Lit(Lit::Str(lit_paths)) => {
+                paths.extend(lit_paths.parse_with(Punctuated::<Path, Token![,]>::parse_terminated)?);
+            }
+            _ => return Err(format_err_spanned!(nested, "{}", error)),
+        }
+    }
+    Ok(paths)
+}
+
+struct MethodConfig {
+    // The storage changes are committed only if the method returns `Ok`
+    transactional: bool,
+    // Nested calls into the method are rejected
+    non_reentrant: bool,
+}
+
+impl MethodConfig {
+    fn new(method_item: &ImplItemMethod, trait_config: &Config) -> Result<Self, Error> {
+        let mut config = Self {
+            transactional: trait_config.transactional,
+            non_reentrant: trait_config.non_reentrant,
+        };
+        config.parse_attributes(method_item)?;
+        Ok(config)
+    }
+
+    fn parse_attributes(&mut self, method_item: &ImplItemMethod) -> Result<(), Error> {
+        for attr in method_item.attrs.iter() {
+            if !attr.path.is_ident("obce") {
+                continue
+            }
+
+            let attrs = attr
+                .parse_args::<AttributeArgs>()
+                .map_err(|error| format_err_spanned!(attr, "unable decode attributes: {}", error,))?;
+
+            for attr in attrs.iter() {
+                match attr {
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("transactional") => {
+                        self.transactional = true;
+                    }
+                    NestedMeta::Meta(Meta::Path(path)) if path.is_ident("non_reentrant") => {
+                        self.non_reentrant = true;
+                    }
+                    _ => {
+                        Err(format_err_spanned!(attr, "unexpected attribute"))?;
+                    }
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
 pub struct ChainExtensionImplementation;
 
 impl ChainExtensionImplementation {
-    pub fn generate(_attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
+    pub fn generate(attrs: TokenStream, input: TokenStream) -> Result<TokenStream, Error> {
         let impl_item: ItemImpl = parse2(input).unwrap();
-        let original_implementation = impl_item.clone();
-        let chain_extension = Self::chain_extension_trait_impl(impl_item)?;
+        let config = Config::new(&impl_item, attrs)?;
+        let mut original_implementation = impl_item.clone();
+        let chain_extension = Self::chain_extension_trait_impl(impl_item, &config)?;
+
+        // Remove all `obce` attributes from implementation's methods
+        original_implementation.items.iter_mut().for_each(|item| {
+            if let ImplItem::Method(method) = item {
+                method.attrs = method
+                    .attrs
+                    .clone()
+                    .into_iter()
+                    .filter(|attr| !attr.path.is_ident("obce"))
+                    .collect();
+            }
+        });
 
         return Ok(quote! {
             // Implementation of the trait for `ExtensionContext` with main logic.
             #original_implementation
 
-            // Implementation of `ChainExtension` from `contract-pallet`
+            // Implementation of `ExtensionDispatch` and `ChainExtension` from `contract-pallet`
             #chain_extension
         })
     }
 
     #[allow(non_snake_case)]
-    fn chain_extension_trait_impl(impl_item: ItemImpl) -> Result<TokenStream, Error> {
+    fn chain_extension_trait_impl(impl_item: ItemImpl, config: &Config) -> Result<TokenStream, Error> {
         let context = Self::split_generics(&impl_item)?;
         let mut main_generics = impl_item.generics.clone();
         main_generics = filter_generics(main_generics, &context.lifetime1);
         main_generics = filter_generics(main_generics, &context.lifetime2);
         main_generics = filter_generics(main_generics, &context.env);
+        let T = context.substrate;
+        if config.kill_switch {
+            main_generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { #T: ::obce::substrate::pallet::Config });
+        }
+        // The allow list of the trait is declared by the definition for every runtime.
+        if let Some((_, path, _)) = &impl_item.trait_ {
+            main_generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { dyn #path: ::obce::substrate::ExtensionAllowList<#T> });
+        }
+        if config.hooks {
+            let extension = &context.extension;
+            main_generics
+                .make_where_clause()
+                .predicates
+                .push(parse_quote! { #extension: ::obce::substrate::ExtensionHooks<#T> });
+        }
         let (main_impls, _, main_where) = main_generics.split_for_impl();
 
         let mut call_generics = impl_item.generics.clone();
@@ -74,7 +297,6 @@ impl ChainExtensionImplementation {
         call_generics = filter_generics(call_generics, &context.lifetime2);
         let (_, _, call_where) = call_generics.split_for_impl();
 
-        let T = context.substrate;
         let E = context.env;
         let extension = context.extension;
         let namespace = quote! { ::obce::substrate::pallet_contracts::chain_extension:: };
@@ -87,56 +309,466 @@ impl ChainExtensionImplementation {
             return Err(format_err_spanned!(impl_item, "expected impl trait block",))
         }
 
-        let methods = impl_item.items.iter().filter_map(|item| {
-            if let ImplItem::Method(method) = item {
-                let hash = into_u32(&method.sig.ident);
-                let method_name = &method.sig.ident;
-                let input_bindings = input_bindings(&method.sig.inputs);
-                let bindings_tuple = input_bindings_tuple(&method.sig.inputs);
-                Some(quote! {
-                    <#dyn_trait as ::obce::codegen::MethodDescription<#hash>>::ID => {
-                        let #bindings_tuple = env.read_as_unbounded(len)?;
-                        let mut context = ::obce::substrate::ExtensionContext::new(self, env);
-                        let result = <_ as #trait_>::#method_name(
-                            &mut context
-                            #(
-                                , #input_bindings
-                            )*
-                        );
-                        // If result is `Result` and `Err` is critical, return from the `call`.
-                        // Otherwise encode the result into the buffer.
-                        let result = ::obce::to_critical_error!(result)?;
-                        <_ as ::scale::Encode>::using_encoded(&result, |w| context.env.write(w, true, None))?;
-                    },
-                })
-            } else {
-                None
+        let methods: Vec<_> = impl_item
+            .items
+            .iter()
+            .filter_map(|item| {
+                if let ImplItem::Method(method) = item {
+                    Some(method)
+                } else {
+                    None
+                }
+            })
+            .collect();
+        let arms = |source: InputSource| {
+            methods
+                .iter()
+                .map(|method| Self::method_arm(method, &trait_, &dyn_trait, &T, config, source))
+                .collect::<Result<Vec<_>, _>>()
+        };
+        let buffer_arms = arms(InputSource::Buffer)?;
+        let batch_arms = arms(InputSource::Batch)?;
+
+        // Old ids of renamed methods are replaced with the current ones.
+        let aliases = methods.iter().map(|method| {
+            let hash = into_u32(&method.sig.ident);
+            let description = quote! { <#dyn_trait as ::obce::codegen::MethodDescription<#hash>> };
+            quote! {
+                if #description::ALIASES.contains(&func_id) {
+                    #description::ID
+                } else
             }
         });
+        let resolve_aliases = quote! {
+            let func_id = #(#aliases)* {
+                func_id
+            };
+        };
+
+        let allow = if let Some(provider) = &config.allow {
+            quote! {
+                ::obce::substrate::ensure_allowed::<#provider, _>(&mut env)?;
+            }
+        } else {
+            quote! {}
+        };
+
+        let kill_switch = |env: TokenStream| {
+            if config.kill_switch {
+                quote! {
+                    #env.charge_weight(::obce::substrate::pallet::Pallet::<#T>::is_disabled_weight())?;
+                    if ::obce::substrate::pallet::Pallet::<#T>::is_disabled(
+                        <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID,
+                        func_id,
+                    ) {
+                        ::core::result::Result::Err(::obce::substrate::sp_runtime::DispatchError::Other(
+                            "DisabledMethod"
+                        ))?
+                    }
+                }
+            } else {
+                quote! {}
+            }
+        };
+
+        let dispatch_kill_switch = kill_switch(quote! { env });
+        let batch_kill_switch = kill_switch(quote! { context.env });
+
+        let dispatch = quote! { ::obce::substrate::ExtensionDispatch<#T, #dyn_trait> };
+        let enabled = if let Some(function) = &config.enabled {
+            quote! {
+                fn enabled() -> ::core::primitive::bool {
+                    #function()
+                }
+            }
+        } else {
+            quote! {}
+        };
+        let chain_extension = if config.dispatch_only {
+            quote! {}
+        } else if config.merge.is_empty() {
+            quote! {
+                impl #main_impls #namespace ChainExtension<#T> for #extension #main_where {
+                    fn call<#E>(&mut self, env: #namespace Environment<#E, #namespace InitState>)
+                        -> ::core::result::Result<#namespace RetVal, ::obce::substrate::sp_runtime::DispatchError>
+                        #call_where
+                    {
+                        <Self as #dispatch>::dispatch(self, env)
+                    }
+
+                    #enabled
+                }
+
+                impl #main_impls #namespace RegisteredChainExtension<#T> for #extension #main_where {
+                    const ID: ::core::primitive::u16 = <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID;
+                }
+            }
+        } else {
+            // The chain extension serves several traits, so calls are routed by the extension id.
+            // Bounds of other implementations are required via their dispatchers.
+            let mut merged_generics = main_generics.clone();
+            for path in config.merge.iter() {
+                merged_generics
+                    .make_where_clause()
+                    .predicates
+                    .push(parse_quote! { #extension: ::obce::substrate::ExtensionDispatch<#T, dyn #path> });
+            }
+            let (merged_impls, _, merged_where) = merged_generics.split_for_impl();
+            let merged = config.merge.iter().map(|path| {
+                quote! {
+                    if ext_id == <dyn #path as ::obce::codegen::ExtensionDescription>::ID {
+                        return <Self as ::obce::substrate::ExtensionDispatch<#T, dyn #path>>::dispatch(self, env)
+                    }
+                }
+            });
+            quote! {
+                impl #merged_impls #namespace ChainExtension<#T> for #extension #merged_where {
+                    fn call<#E>(&mut self, env: #namespace Environment<#E, #namespace InitState>)
+                        -> ::core::result::Result<#namespace RetVal, ::obce::substrate::sp_runtime::DispatchError>
+                        #call_where
+                    {
+                        let ext_id = env.ext_id();
+                        if ext_id == <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID {
+                            return <Self as #dispatch>::dispatch(self, env)
+                        }
+                        #(#merged)*
+                        ::core::result::Result::Err(::obce::substrate::sp_runtime::DispatchError::Other(
+                            "InvalidExtensionId"
+                        ))
+                    }
+
+                    #enabled
+                }
+            }
+        };
 
         Ok(quote! {
-            impl #main_impls #namespace ChainExtension<#T> for #extension #main_where {
-                fn call<#E>(&mut self, env: #namespace Environment<#E, #namespace InitState>)
+            impl #main_impls #dispatch for #extension #main_where {
+                fn dispatch<#E>(&mut self, env: #namespace Environment<#E, #namespace InitState>)
                     -> ::core::result::Result<#namespace RetVal, ::obce::substrate::sp_runtime::DispatchError>
                     #call_where
                 {
                     let mut env = env.buf_in_buf_out();
                     let len = env.in_len();
+                    let func_id = env.func_id();
+                    #resolve_aliases
+                    <#dyn_trait as ::obce::substrate::ExtensionAllowList<#T>>::ensure_allowed(&mut env)?;
+                    #allow
+                    #dispatch_kill_switch
+
+                    // Methods that handle the status code return errors via it instead of the output.
+                    let status = ::core::cell::Cell::new(0);
+
+                    if func_id == ::obce::codegen::BATCH_FUNC_ID {
+                        // The size of the batch is not bounded, so reading of it is charged per byte.
+                        env.charge_weight(
+                            <<#T as ::obce::substrate::pallet_contracts::Config>::Schedule as ::obce::substrate::frame_support::traits::Get<
+                                ::obce::substrate::pallet_contracts::Schedule<#T>,
+                            >>::get()
+                            .host_fn_weights
+                            .input_per_byte
+                            .saturating_mul(len as ::core::primitive::u64),
+                        )?;
+                        let calls: ::obce::substrate::sp_std::vec::Vec<(
+                            ::core::primitive::u16,
+                            ::obce::substrate::sp_std::vec::Vec<::core::primitive::u8>,
+                        )> = env.read_as_unbounded(len)?;
+                        let context = &mut ::obce::substrate::ExtensionContext::new(self, env);
+                        let mut results = ::obce::substrate::sp_std::vec::Vec::with_capacity(calls.len());
+
+                        // Each call is executed in order, a critical error stops the whole batch.
+                        for (func_id, input) in calls {
+                            #resolve_aliases
+                            #batch_kill_switch
+                            match func_id {
+                                #(#batch_arms)*
+                                _ => ::core::result::Result::Err(::obce::substrate::sp_runtime::DispatchError::Other(
+                                    "InvalidFunctionId"
+                                ))?,
+                            };
+                        }
+
+                        <_ as ::scale::Encode>::using_encoded(&results, |w| context.env.write(w, true, None))?;
+                    } else {
+                        let context = &mut ::obce::substrate::ExtensionContext::new(self, env);
+
+                        match func_id {
+                            #(#buffer_arms)*
+                            _ => ::core::result::Result::Err(::obce::substrate::sp_runtime::DispatchError::Other(
+                                "InvalidFunctionId"
+                            ))?,
+                        };
+                    }
+
+                    Ok(#namespace RetVal::Converging(status.get()))
+                }
+            }
+
+            #chain_extension
+        })
+    }
+
+    #[allow(non_snake_case)]
+    fn method_arm(
+        method: &ImplItemMethod,
+        trait_: &Path,
+        dyn_trait: &TokenStream,
+        T: &GenericArgument,
+        config: &Config,
+        source: InputSource,
+    ) -> Result<TokenStream, Error> {
+        let method_config = MethodConfig::new(method, config)?;
+        let hash = into_u32(&method.sig.ident);
+        let method_name = &method.sig.ident;
+        let input_bindings = input_bindings(&method.sig.inputs);
+        let bindings_tuple = input_bindings_tuple(&method.sig.inputs);
+        let description = quote! { <#dyn_trait as ::obce::codegen::MethodDescription<#hash>> };
 
-                    match env.func_id() {
-                        #(#methods)*
-                        _ => ::core::result::Result::Err(::obce::substrate::sp_runtime::DispatchError::Other(
-                            "InvalidFunctionId"
-                        ))?,
-                    };
+        let (trace_start, trace) = if let Some(target) = &config.trace {
+            let method_name = method_name.to_string();
+            let input_len = match source {
+                InputSource::Buffer => quote! { len },
+                InputSource::Batch => quote! { input.len() },
+            };
+            (
+                quote! {
+                    let gas_left = context.env.ext().gas_meter().gas_left();
+                },
+                quote! {
+                    let weight = gas_left.saturating_sub(context.env.ext().gas_meter().gas_left());
+                    // Critical errors include failures of decoding the input, so the error is logged too.
+                    if let ::core::result::Result::Err(error) = &result {
+                        ::obce::substrate::log::debug!(
+                            target: #target,
+                            "{} (func_id: {}, input_len: {}, weight: {:?}): {:?} {:?}",
+                            #method_name,
+                            #description::ID,
+                            #input_len,
+                            weight,
+                            ::obce::call_outcome!(&result),
+                            error,
+                        );
+                    } else {
+                        ::obce::substrate::log::debug!(
+                            target: #target,
+                            "{} (func_id: {}, input_len: {}, weight: {:?}): {:?}",
+                            #method_name,
+                            #description::ID,
+                            #input_len,
+                            weight,
+                            ::obce::call_outcome!(&result),
+                        );
+                    }
+                },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        let decode = match source {
+            InputSource::Buffer => {
+                quote! {
+                    let #bindings_tuple = context.env.read_as_unbounded(len)?;
+                }
+            }
+            InputSource::Batch => {
+                quote! {
+                    let #bindings_tuple = <_ as ::scale::DecodeLimit>::decode_all_with_depth_limit(
+                        ::obce::substrate::MAX_DECODE_NESTING,
+                        &mut input.as_slice(),
+                    )
+                    .map_err(|_| ::obce::substrate::pallet_contracts::Error::<#T>::DecodingFailed)?;
+                }
+            }
+        };
 
-                    Ok(#namespace RetVal::Converging(0))
+        let output = match source {
+            InputSource::Buffer => {
+                let encode = format_ident!("__obce_output_{}", method_name);
+                quote! {
+                    match <_ as #trait_>::#encode(&*context, result) {
+                        ::core::result::Result::Ok(result) => {
+                            <_ as ::scale::Encode>::using_encoded(&result, |w| context.env.write(
+                                w,
+                                true,
+                                #description::OUTPUT_WEIGHT_PER_BYTE
+                                    .map(::obce::substrate::frame_support::weights::Weight::from_ref_time),
+                            ))?
+                        }
+                        ::core::result::Result::Err(code) => status.set(code),
+                    }
                 }
             }
+            InputSource::Batch => {
+                quote! {
+                    let output = <_ as ::scale::Encode>::encode(&result);
+                    if let ::core::option::Option::Some(weight) = #description::OUTPUT_WEIGHT_PER_BYTE {
+                        context.env.charge_weight(
+                            ::obce::substrate::frame_support::weights::Weight::from_ref_time(weight)
+                                .saturating_mul(output.len() as ::core::primitive::u64),
+                        )?;
+                    }
+                    results.push(output);
+                }
+            }
+        };
+
+        let validate = format_ident!("__obce_validate_{}", method_name);
+        let validate = quote! {
+            <_ as #trait_>::#validate(
+                &*context
+                #(
+                    , &#input_bindings
+                )*
+            )
+        };
 
-            impl #main_impls #namespace RegisteredChainExtension<#T> for #extension #main_where {
-                const ID: ::core::primitive::u16 = <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID;
+        let call = quote! {
+            ::obce::to_critical_error!(<_ as #trait_>::#method_name(
+                &mut *context
+                #(
+                    , #input_bindings
+                )*
+            ))
+        };
+        let execute = if method_config.transactional {
+            quote! {
+                ::obce::substrate::transactional(|| {
+                    let result = #call;
+                    let outcome = ::obce::call_outcome!(&result);
+                    (result, outcome)
+                })?
             }
+        } else {
+            call.clone()
+        };
+
+        let (before_call, after_call) = if config.hooks {
+            (
+                quote! {
+                    ::obce::substrate::ExtensionHooks::<#T>::before_call(
+                        &mut *context.storage,
+                        #description::ID,
+                        &mut context.env,
+                    )?;
+                },
+                quote! {
+                    ::obce::substrate::ExtensionHooks::<#T>::after_call(
+                        &mut *context.storage,
+                        #description::ID,
+                        &::obce::call_outcome!(&result),
+                    );
+                },
+            )
+        } else {
+            (quote! {}, quote! {})
+        };
+
+        let call_method = quote! {
+            ::core::result::Result::Ok(if let ::core::option::Option::Some(invalid) = #validate {
+                ::core::result::Result::Ok(invalid)
+            } else if #description::VIEW {
+                ::obce::substrate::with_rollback(|| #call)?
+            } else {
+                #execute
+            })
+        };
+        let call_method = if method_config.non_reentrant {
+            quote! {
+                ::obce::substrate::ReentrancyGuard::execute(
+                    <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID,
+                    || -> ::core::result::Result<_, ::obce::substrate::sp_runtime::DispatchError> {
+                        #call_method
+                    },
+                )
+            }
+        } else {
+            call_method
+        };
+
+        // The batch call returns only encoded outputs, so methods that report errors
+        // by the status code are rejected there, as they are not callable from the ink! side.
+        let reject_status = match source {
+            InputSource::Buffer => quote! {},
+            InputSource::Batch => {
+                quote! {
+                    if #description::HANDLE_STATUS {
+                        ::core::result::Result::Err(::obce::substrate::sp_runtime::DispatchError::Other(
+                            "StatusCodeInBatch"
+                        ))?
+                    }
+                }
+            }
+        };
+
+        // Types replaced by `substrate_type` are known only here, so their shapes are compared by
+        // the dispatcher. The comparison is expensive, so it is done only in debug builds and tests.
+        let same_shape = format_ident!("__obce_same_shape_{}", method_name);
+        let method_name_str = method_name.to_string();
+        let same_shape = quote! {
+            ::core::debug_assert!(
+                <_ as #trait_>::#same_shape(&*context),
+                "`substrate_type`s of `{}` encode differently from the declared types",
+                #method_name_str,
+            );
+        };
+
+        let body = quote! {
+            #same_shape
+            #reject_status
+            #before_call
+            #trace_start
+            // Errors after `before_call` are collected into the `result`,
+            // so `after_call` observes every outcome of the method.
+            let result = (|| -> ::core::result::Result<_, ::obce::substrate::sp_runtime::DispatchError> {
+                #decode
+                #call_method
+            })()
+            .and_then(::core::convert::identity);
+            #trace
+            #after_call
+            // If result is `Result` and `Err` is critical, return from the `call`.
+            // Otherwise encode the result into the output.
+            let result = result?;
+            #output
+        };
+
+        // Each layer wraps the next one, so the first layer is the outermost.
+        let body = if config.layers.is_empty() {
+            body
+        } else {
+            let name = method_name.to_string();
+            let mut next = quote! {
+                |context| {
+                    #body
+                    ::core::result::Result::Ok(())
+                }
+            };
+            let mut layered = quote! {};
+            for layer in config.layers.iter().rev() {
+                layered = quote! {
+                    <#layer as ::obce::substrate::Layer<#T>>::call(
+                        context,
+                        <#dyn_trait as ::obce::codegen::ExtensionDescription>::ID,
+                        ::obce::codegen::MethodMetadata {
+                            name: #name,
+                            id: #description::ID,
+                            view: #description::VIEW,
+                        },
+                        #next,
+                    )
+                };
+                next = quote! { |context| #layered };
+            }
+            quote! {
+                #layered?;
+            }
+        };
+
+        Ok(quote! {
+            #description::ID => {
+                #body
+            },
         })
     }
 
@@ -189,6 +821,15 @@ impl ChainExtensionImplementation {
     }
 }
 
+/// Where the method's input comes from.
+#[derive(Clone, Copy)]
+enum InputSource {
+    // The input buffer of the contract
+    Buffer,
+    // The input of the call inside of the batch
+    Batch,
+}
+
 struct ExtensionContext {
     // Lifetime `'a`
     lifetime1: GenericArgument,
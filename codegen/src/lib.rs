@@ -19,12 +19,15 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+mod conversion;
 mod definition;
 mod implementation;
+mod lock;
 mod types;
 mod utils;
 
 pub use crate::{
+    conversion::ChainExtensionConversion,
     definition::ChainExtensionDefinition,
     implementation::ChainExtensionImplementation,
 };
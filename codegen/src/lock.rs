@@ -0,0 +1,203 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! The lockfile with ids of extensions and their methods.
+//!
+//! Each line of the lockfile is `crate::Trait = 0x0001` for the extension or
+//! `crate::Trait::method = 0x0001, 0x0002` for the method, where the first id is the current one
+//! and others are aliases. Methods of legacy definitions are locked by their raw ids.
+//! Lines starting with `#` are comments.
+//!
+//! The trait is qualified by the name of the crate. Traits with the same name in one crate
+//! should use different lockfiles.
+//!
+//! Each crate should use its own lockfile. Macros of one crate are expanded sequentially,
+//! but cargo builds crates in parallel, and the bless mode rewrites the whole lockfile,
+//! so updates of crates that share the lockfile may be lost.
+
+use crate::format_err_spanned;
+use proc_macro2::TokenStream;
+use quote::{
+    quote,
+    ToTokens,
+};
+use std::{
+    collections::BTreeMap,
+    env,
+    fs,
+    path::PathBuf,
+};
+use syn::Error;
+
+/// The environment variable that regenerates the lockfile instead of the check.
+const BLESS_ENV: &str = "OBCE_BLESS";
+
+const HEADER: &str = "# Ids of chain extensions and their methods generated by OBCE.\n\
+                      # Don't edit it manually, rebuild with `OBCE_BLESS=1` to update it.\n";
+
+/// Checks that the `extension_id` and ids of `methods` of the trait match the lockfile,
+/// or updates the lockfile if the bless mode is enabled.
+///
+/// Removed or changed ids fail the check, while new methods and aliases are allowed.
+/// Ids of methods are the current id followed by aliases.
+///
+/// Returns items that make the compiler rebuild the crate when the lockfile or the bless mode changes.
+pub fn check_ids<T: ToTokens>(
+    span: &T,
+    path: &str,
+    trait_name: &str,
+    extension_id: u16,
+    methods: &[(String, Vec<u32>)],
+) -> Result<TokenStream, Error> {
+    let path = match env::var_os("CARGO_MANIFEST_DIR") {
+        Some(dir) => PathBuf::from(dir).join(path),
+        None => PathBuf::from(path),
+    };
+    let trait_key = match env::var("CARGO_CRATE_NAME") {
+        Ok(crate_name) => format!("{}::{}", crate_name, trait_name),
+        Err(_) => trait_name.to_string(),
+    };
+
+    let mut current = BTreeMap::new();
+    current.insert(trait_key.clone(), vec![extension_id as u32]);
+    for (method, ids) in methods {
+        current.insert(format!("{}::{}", trait_key, method), ids.clone());
+    }
+
+    let content = match fs::read_to_string(&path) {
+        Ok(content) => Some(content),
+        Err(error) if error.kind() == std::io::ErrorKind::NotFound => None,
+        Err(error) => {
+            return Err(format_err_spanned!(
+                span,
+                "unable to read {}: {}",
+                path.display(),
+                error
+            ))
+        }
+    };
+    let mut locked = match &content {
+        Some(content) => parse(content).map_err(|error| format_err_spanned!(span, "{}: {}", path.display(), error))?,
+        None => BTreeMap::new(),
+    };
+    let is_own = |key: &String| key == &trait_key || key.starts_with(&format!("{}::", trait_key));
+
+    if bless() {
+        locked.retain(|key, _| !is_own(key));
+        locked.extend(current);
+        fs::write(&path, format(&locked))
+            .map_err(|error| format_err_spanned!(span, "unable to write {}: {}", path.display(), error))?;
+    } else if content.is_none() {
+        return Err(format_err_spanned!(
+            span,
+            "the lockfile {} doesn't exist, build with `{}=1` to create it",
+            path.display(),
+            BLESS_ENV,
+        ))
+    } else {
+        locked.retain(|key, _| is_own(key));
+        let diff = diff(&locked, &current);
+        if !diff.is_empty() {
+            return Err(format_err_spanned!(
+                span,
+                "ids of `{}` don't match the lockfile {}:\n{}\nIt breaks deployed contracts, use `id` or `aliases` \
+                 to keep old ids. If the change is intended, build with `{}=1` to update the lockfile.",
+                trait_name,
+                path.display(),
+                diff,
+                BLESS_ENV,
+            ))
+        }
+    }
+
+    let path = path.display().to_string();
+    Ok(quote! {
+        const _: &[::core::primitive::u8] = ::core::include_bytes!(#path);
+        const _: ::core::option::Option<&str> = ::core::option_env!(#BLESS_ENV);
+    })
+}
+
+fn bless() -> bool {
+    matches!(env::var(BLESS_ENV), Ok(value) if !value.is_empty() && value != "0")
+}
+
+fn parse(content: &str) -> Result<BTreeMap<String, Vec<u32>>, String> {
+    let mut entries = BTreeMap::new();
+    for (number, line) in content.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue
+        }
+
+        let (key, ids) = line
+            .split_once('=')
+            .ok_or_else(|| format!("line {}: expected `name = id`", number + 1))?;
+        let ids = ids
+            .split(',')
+            .map(|id| {
+                let id = id.trim();
+                u32::from_str_radix(id.trim_start_matches("0x"), 16)
+                    .map_err(|error| format!("line {}: invalid id `{}`: {}", number + 1, id, error))
+            })
+            .collect::<Result<_, _>>()?;
+        entries.insert(key.trim().to_string(), ids);
+    }
+    Ok(entries)
+}
+
+fn format(entries: &BTreeMap<String, Vec<u32>>) -> String {
+    let mut content = HEADER.to_string();
+    for (key, ids) in entries {
+        content.push_str(&format!("{} = {}\n", key, format_ids(ids)));
+    }
+    content
+}
+
+fn format_ids(ids: &[u32]) -> String {
+    ids.iter()
+        .map(|id| {
+            if *id > u16::MAX as u32 {
+                format!("{:#010x}", id)
+            } else {
+                format!("{:#06x}", id)
+            }
+        })
+        .collect::<Vec<_>>()
+        .join(", ")
+}
+
+/// Returns entries of the lockfile that were removed or changed. The current id should be
+/// the same and all locked aliases should be kept, but new aliases and entries are allowed.
+fn diff(locked: &BTreeMap<String, Vec<u32>>, current: &BTreeMap<String, Vec<u32>>) -> String {
+    let mut diff = String::new();
+    for (key, old) in locked {
+        match current.get(key) {
+            Some(new) if new.first() == old.first() && old.iter().all(|id| new.contains(id)) => {}
+            new => {
+                diff.push_str(&format!("- {} = {}\n", key, format_ids(old)));
+                if let Some(new) = new {
+                    diff.push_str(&format!("+ {} = {}\n", key, format_ids(new)));
+                }
+            }
+        }
+    }
+    diff
+}
@@ -19,29 +19,141 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+use proc_macro2::{
+    Delimiter,
+    TokenStream,
+    TokenTree,
+};
+use quote::ToTokens;
 use syn::{
     ext::IdentExt,
     parse::{
         Parse,
         ParseStream,
     },
+    punctuated::Punctuated,
+    Lit,
+    LitStr,
+    Meta,
+    MetaList,
+    MetaNameValue,
     NestedMeta,
+    Path,
+    Token,
 };
 
+/// Arguments of the attribute.
+///
+/// In addition to the `syn::AttributeArgs` it supports non-literal values like
+/// `name = path::to::Type` and `name(1..=100)`. Such values are converted into
+/// string literals, so they can be parsed later according to the argument.
+///
+/// Only arguments from [`PATH_ARGUMENTS`] accept non-literal values, other arguments
+/// like `id = MY_CONST` are rejected instead of being parsed from the string.
 pub struct AttributeArgs(Vec<NestedMeta>);
 
+/// Arguments whose values are paths, types or cfg predicates.
+const PATH_ARGUMENTS: &[&str] = &[
+    "allow",
+    "enabled",
+    "validate",
+    "ink_type",
+    "substrate_type",
+    "error_code",
+    "ink_cfg",
+    "substrate_cfg",
+];
+
 impl Parse for AttributeArgs {
     fn parse(input: ParseStream) -> syn::Result<Self> {
         let mut attrs = Vec::new();
         while input.peek(syn::Ident::peek_any) {
-            attrs.push(input.parse()?);
+            attrs.push(parse_nested_meta(input)?);
             if input.is_empty() {
                 break
             }
             let _: syn::token::Comma = input.parse()?;
         }
-        Ok(AttributeArgs { 0: attrs })
+        Ok(AttributeArgs(attrs))
+    }
+}
+
+fn parse_nested_meta(input: ParseStream) -> syn::Result<NestedMeta> {
+    let path = input.call(Path::parse_mod_style)?;
+
+    if input.peek(Token![=]) {
+        let eq_token = input.parse()?;
+        let lit = if input.peek(Lit) {
+            input.parse()?
+        } else if PATH_ARGUMENTS.iter().any(|argument| path.is_ident(argument)) {
+            Lit::Str(tokens_to_lit(take_until_comma(input)?)?)
+        } else {
+            return Err(input.error(format!("expected literal value of `{}`", path.to_token_stream())))
+        };
+        return Ok(NestedMeta::Meta(Meta::NameValue(MetaNameValue { path, eq_token, lit })))
+    }
+
+    if input.peek(syn::token::Paren) {
+        let (paren_token, content) = input.step(|cursor| {
+            if let Some((content, span, rest)) = cursor.group(Delimiter::Parenthesis) {
+                Ok(((syn::token::Paren(span), content.token_stream()), rest))
+            } else {
+                Err(cursor.error("expected parentheses"))
+            }
+        })?;
+        let nested =
+            match syn::parse::Parser::parse2(Punctuated::<NestedMeta, Token![,]>::parse_terminated, content.clone()) {
+                Ok(nested) => nested,
+                Err(_) => {
+                    let mut nested = Punctuated::new();
+                    nested.push(NestedMeta::Lit(Lit::Str(tokens_to_lit(content)?)));
+                    nested
+                }
+            };
+        return Ok(NestedMeta::Meta(Meta::List(MetaList {
+            path,
+            paren_token,
+            nested,
+        })))
     }
+
+    Ok(NestedMeta::Meta(Meta::Path(path)))
+}
+
+fn take_until_comma(input: ParseStream) -> syn::Result<TokenStream> {
+    input.step(|cursor| {
+        let mut tokens = TokenStream::new();
+        let mut rest = *cursor;
+        // Commas inside of generic arguments don't end the value
+        let mut depth = 0usize;
+        let mut arrow = false;
+        while let Some((tt, next)) = rest.token_tree() {
+            if let TokenTree::Punct(punct) = &tt {
+                match punct.as_char() {
+                    ',' if depth == 0 => break,
+                    '<' => depth += 1,
+                    '>' if !arrow => depth = depth.saturating_sub(1),
+                    _ => {}
+                }
+                arrow = punct.as_char() == '-';
+            } else {
+                arrow = false;
+            }
+            tokens.extend(Some(tt));
+            rest = next;
+        }
+        Ok((tokens, rest))
+    })
+}
+
+fn tokens_to_lit(tokens: TokenStream) -> syn::Result<LitStr> {
+    let span = tokens
+        .clone()
+        .into_iter()
+        .next()
+        .map(|token| token.span())
+        .ok_or_else(|| syn::Error::new(proc_macro2::Span::call_site(), "expected value"))?;
+    Ok(LitStr::new(&tokens.to_string(), span))
 }
 
 impl std::ops::Deref for AttributeArgs {
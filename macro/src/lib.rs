@@ -24,11 +24,53 @@
 use proc_macro::TokenStream;
 
 use obce_codegen::{
+    ChainExtensionConversion,
     ChainExtensionDefinition,
     ChainExtensionImplementation,
 };
 
-// TODO: Add comments with examples
+/// Defines the chain extension trait that is shared by the ink! side and the substrate side.
+///
+/// The ink! side gets default bodies of methods that call the chain extension, the `{Trait}Ref` and
+/// `{Trait}DynRef` caller types and the `{Trait}Batch` builder. The substrate side is implemented with
+/// `#[obce::implementation]`. Arguments of the attribute:
+///
+/// - `id = 0x13` or `id = "name"` sets the extension id, by default it is calculated from the name of the trait.
+/// - `output_weight_per_byte = N` charges the weight per byte of the output of all methods.
+/// - `ink_cfg = ..` and `substrate_cfg = ..` override cfgs of sides, by default `feature = "ink"`
+///   and `feature = "substrate"`.
+/// - `both` generates both sides in `ink` and `substrate` modules, so they can be used in one crate.
+/// - `lock = "path/to/obce.lock"` fails the build if locked ids of the trait or its methods are removed or changed.
+///   Build with `OBCE_BLESS=1` to update the lockfile. Crates are built in parallel, so each crate should
+///   use its own lockfile.
+/// - `legacy` calculates ids from `raw_id`s of methods, like `#[ink::chain_extension]` does.
+/// - `error_code = ErrorCode` returns errors of methods by the status code instead of the output. The error
+///   of each method that handles the status code should implement `Into<u32>`, usually by
+///   `impl From<ErrorCode> for u32`, otherwise the definition doesn't compile.
+/// - `allow = Provider` rejects contracts that are not allowed by the `AllowListProvider` in all
+///   implementations of the trait.
+///
+/// Arguments of `#[obce(..)]` on methods:
+///
+/// - `id = 0x1` or `id = "name"` sets the method id, by default it is calculated from the name of the method.
+/// - `raw_id = 0x0001_0003` sets the full id of the method of the `legacy` definition.
+/// - `aliases(0x12, "old_name")` keeps old ids of the method callable.
+/// - `output_weight_per_byte = N` charges the weight per byte of the output of the method.
+/// - `view` executes the method without modification of the state.
+/// - `handle_status = false` encodes the error into the output even if the trait has `error_code`.
+/// - `output_result` encodes the whole `Ok` result into the output of the method that handles the status code,
+///   like ink!'s `returns_result = true` does.
+/// - `ink_type = T` and `substrate_type = T` replace the type of the output on the side.
+///
+/// Arguments of `#[obce(..)]` on arguments of methods:
+///
+/// - `ink_type = T` and `substrate_type = T` replace the type of the argument on the side.
+/// - `range(1..=100)`, `max_len = 4` and `validate = path::to::function` reject invalid arguments before
+///   the method is called. The method should return `Result<_, E>` where `E: From<InvalidArgument>`.
+///
+/// The declared type and the `substrate_type` of the argument or the output should implement `TypeInfo`.
+/// In debug builds the dispatcher asserts that their `scale_info` shapes are the same, so types that
+/// depend on the runtime, like `T::AccountId`, are checked too.
 #[proc_macro_attribute]
 pub fn definition(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
     match ChainExtensionDefinition::generate(attrs.into(), trait_item.into()) {
@@ -37,7 +79,24 @@ pub fn definition(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
     }
 }
 
-// TODO: Add comments with examples
+/// Implements the substrate side of the OBCE definition for `ExtensionContext`.
+///
+/// It generates the dispatcher of methods and implements `ChainExtension` and
+/// `RegisteredChainExtension` for the `Extension` type. Arguments of the attribute:
+///
+/// - `trace` or `trace = "target"` logs each call of the method.
+/// - `transactional` and `non_reentrant` are applied to all methods, also available as `#[obce(..)]` on methods.
+///   `non_reentrant` methods of one trait share the guard, so none of them can be entered while another runs.
+/// - `kill_switch` rejects methods disabled in `obce::substrate::pallet`.
+/// - `allow = Provider` rejects contracts that are not allowed by the `AllowListProvider`.
+/// - `hooks` calls `ExtensionHooks` of the `Extension` around each method.
+/// - `layers(A, B)` wraps each method into `Layer`s, the first one is the outermost.
+/// - `enabled = path::to::function` overrides `ChainExtension::enabled`.
+/// - `merge(OtherTrait)` routes calls by the extension id to implementations of other traits made with
+///   `dispatch_only`. The merged extension serves several extension ids, so `RegisteredChainExtension`
+///   is not implemented and the extension can't be a part of a tuple of chain extensions. Use it as
+///   the only `ChainExtension` of `pallet_contracts`.
+/// - `dispatch_only` generates only `ExtensionDispatch`, the trait should be merged by another implementation.
 #[proc_macro_attribute]
 pub fn implementation(attrs: TokenStream, impl_item: TokenStream) -> TokenStream {
     match ChainExtensionImplementation::generate(attrs.into(), impl_item.into()) {
@@ -45,3 +104,18 @@ pub fn implementation(attrs: TokenStream, impl_item: TokenStream) -> TokenStream
         Err(err) => err.to_compile_error().into(),
     }
 }
+
+/// Converts the ink! 3 `#[ink::chain_extension]` trait into the OBCE definition.
+///
+/// Replace `#[ink::chain_extension]` with `#[obce::from_ink]` to keep ids of methods and
+/// the handling of the status code. Arguments are passed to `#[obce::definition]`.
+///
+/// Methods with `returns_result = false` return `Result<_, ErrorCode>` and write only the `Ok` value,
+/// methods with `returns_result = true` keep their output and write the whole `Result`, as ink! 3 does.
+#[proc_macro_attribute]
+pub fn from_ink(attrs: TokenStream, trait_item: TokenStream) -> TokenStream {
+    match ChainExtensionConversion::generate(attrs.into(), trait_item.into()) {
+        Ok(traits) => traits.into(),
+        Err(err) => err.to_compile_error().into(),
+    }
+}
@@ -19,14 +19,54 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+mod shape;
+
+pub use scale_info::TypeInfo;
+pub use shape::same_shape;
+
+/// The function id reserved for the batch call. The input of the batch call is
+/// `Vec<(u16, Vec<u8>)>` with pairs of function id and encoded input. The output
+/// is `Vec<Vec<u8>>` with encoded results of each call.
+pub const BATCH_FUNC_ID: u16 = u16::MAX;
+
+/// Metadata of the chain extension's method.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct MethodMetadata {
+    pub name: &'static str,
+    pub id: u16,
+    /// The method is executed without modification of the state.
+    pub view: bool,
+}
+
+/// The argument of the chain extension's method is rejected by `#[obce(validate = ..)]`,
+/// `#[obce(range(..))]` or `#[obce(max_len = ..)]` of the definition.
+///
+/// The method with validated arguments should return `Result<_, E>` where `E: From<InvalidArgument>`,
+/// so the error is returned to the contract as non-critical.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub struct InvalidArgument {
+    pub method: &'static str,
+    pub argument: &'static str,
+}
+
 // TODO: Add comments
 pub trait ExtensionDescription {
     const ID: u16;
+    const METHODS: &'static [MethodMetadata];
 }
 
 // TODO: Add comments
 pub trait MethodDescription<const METHOD_HASH: u32> {
     const ID: u16;
+    /// The weight charged per byte of the output written into the contract's buffer.
+    const OUTPUT_WEIGHT_PER_BYTE: Option<u64>;
+    /// The method is executed in the storage layer that is always rolled back.
+    const VIEW: bool;
+    /// Previous ids of the method. The substrate side accepts them in addition to the `ID`.
+    const ALIASES: &'static [u16];
+    /// Errors of the method are reported by the status code of the call instead of the output,
+    /// so the method can't be a part of the batch call.
+    const HANDLE_STATUS: bool;
     type Input;
     type Output;
 }
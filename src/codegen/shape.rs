@@ -0,0 +1,190 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use scale_info::{
+    form::PortableForm,
+    prelude::{
+        collections::BTreeSet,
+        vec::Vec,
+    },
+    Field,
+    MetaType,
+    PortableRegistry,
+    Registry,
+    TypeDef,
+    TypeDefPrimitive,
+    TypeInfo,
+};
+
+/// Returns `true` if `A` and `B` have the same SCALE encoding according to their `scale_info` metadata.
+///
+/// Names, paths and docs are ignored. Structs and tuples are compared by their fields,
+/// and a struct or a tuple with only one field is the same as that field.
+pub fn same_shape<A: TypeInfo + 'static, B: TypeInfo + 'static>() -> bool {
+    let mut registry = Registry::new();
+    let a = registry.register_type(&MetaType::new::<A>()).id;
+    let b = registry.register_type(&MetaType::new::<B>()).id;
+    let registry = PortableRegistry::from(registry);
+
+    ShapeComparator {
+        registry: &registry,
+        visited: BTreeSet::new(),
+    }
+    .compare(a, b)
+}
+
+struct ShapeComparator<'a> {
+    registry: &'a PortableRegistry,
+    // Pairs of types that are already being compared. It allows to compare recursive types.
+    visited: BTreeSet<(u32, u32)>,
+}
+
+impl<'a> ShapeComparator<'a> {
+    fn compare(&mut self, a: u32, b: u32) -> bool {
+        let a = self.unwrap(a);
+        let b = self.unwrap(b);
+        if a == b || !self.visited.insert((a, b)) {
+            return true
+        }
+
+        let (a_def, b_def) = match (self.registry.resolve(a), self.registry.resolve(b)) {
+            (Some(a), Some(b)) => (&a.type_def, &b.type_def),
+            _ => return false,
+        };
+
+        match (a_def, b_def) {
+            (TypeDef::Composite(_) | TypeDef::Tuple(_), TypeDef::Composite(_) | TypeDef::Tuple(_)) => {
+                let a_fields = self.fields(a);
+                let b_fields = self.fields(b);
+                self.compare_all(&a_fields, &b_fields)
+            }
+            (TypeDef::Variant(a), TypeDef::Variant(b)) => {
+                let mut a_variants: Vec<_> = a.variants.iter().collect();
+                let mut b_variants: Vec<_> = b.variants.iter().collect();
+                a_variants.sort_by_key(|variant| variant.index);
+                b_variants.sort_by_key(|variant| variant.index);
+
+                a_variants.len() == b_variants.len()
+                    && a_variants.iter().zip(b_variants.iter()).all(|(a, b)| {
+                        a.index == b.index && self.compare_all(&field_types(&a.fields), &field_types(&b.fields))
+                    })
+            }
+            (TypeDef::Sequence(a), TypeDef::Sequence(b)) => self.compare(a.type_param.id, b.type_param.id),
+            (TypeDef::Array(a), TypeDef::Array(b)) => a.len == b.len && self.compare(a.type_param.id, b.type_param.id),
+            (TypeDef::Compact(a), TypeDef::Compact(b)) => self.compare(a.type_param.id, b.type_param.id),
+            (TypeDef::BitSequence(a), TypeDef::BitSequence(b)) => {
+                self.compare(a.bit_store_type.id, b.bit_store_type.id)
+                    && self.compare(a.bit_order_type.id, b.bit_order_type.id)
+            }
+            (TypeDef::Primitive(a), TypeDef::Primitive(b)) => a == b,
+            // `String` is encoded in the same way as `Vec<u8>`.
+            (TypeDef::Primitive(TypeDefPrimitive::Str), TypeDef::Sequence(sequence))
+            | (TypeDef::Sequence(sequence), TypeDef::Primitive(TypeDefPrimitive::Str)) => {
+                matches!(
+                    self.registry
+                        .resolve(self.unwrap(sequence.type_param.id))
+                        .map(|ty| &ty.type_def),
+                    Some(TypeDef::Primitive(TypeDefPrimitive::U8))
+                )
+            }
+            _ => false,
+        }
+    }
+
+    fn compare_all(&mut self, a: &[u32], b: &[u32]) -> bool {
+        a.len() == b.len() && a.iter().zip(b.iter()).all(|(a, b)| self.compare(*a, *b))
+    }
+
+    /// Returns the type of the only field if the type is a struct or a tuple with one field.
+    fn unwrap(&self, mut id: u32) -> u32 {
+        while let [field] = self.fields(id).as_slice() {
+            id = *field;
+        }
+        id
+    }
+
+    fn fields(&self, id: u32) -> Vec<u32> {
+        match self.registry.resolve(id).map(|ty| &ty.type_def) {
+            Some(TypeDef::Composite(composite)) => field_types(&composite.fields),
+            Some(TypeDef::Tuple(tuple)) => tuple.fields.iter().map(|field| field.id).collect(),
+            _ => Vec::new(),
+        }
+    }
+}
+
+fn field_types(fields: &[Field<PortableForm>]) -> Vec<u32> {
+    fields.iter().map(|field| field.ty.id).collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::same_shape;
+    use scale_info::TypeInfo;
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    struct AccountId([u8; 32]);
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    struct Pair {
+        first: u32,
+        second: AccountId,
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum Recursive {
+        Leaf(u8),
+        Node(Vec<Recursive>),
+    }
+
+    #[allow(dead_code)]
+    #[derive(TypeInfo)]
+    enum Tree {
+        Value(u8),
+        Children(Vec<Tree>),
+    }
+
+    #[test]
+    fn newtypes_are_transparent() {
+        assert!(same_shape::<AccountId, [u8; 32]>());
+        assert!(!same_shape::<AccountId, [u8; 20]>());
+    }
+
+    #[test]
+    fn structs_are_compared_by_fields() {
+        assert!(same_shape::<Pair, (u32, [u8; 32])>());
+        assert!(!same_shape::<Pair, ([u8; 32], u32)>());
+    }
+
+    #[test]
+    fn strings_are_bytes() {
+        assert!(same_shape::<String, Vec<u8>>());
+        assert!(!same_shape::<String, Vec<u16>>());
+    }
+
+    #[test]
+    fn recursive_types() {
+        assert!(same_shape::<Recursive, Tree>());
+        assert!(!same_shape::<Recursive, Option<u8>>());
+    }
+}
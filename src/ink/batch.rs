@@ -0,0 +1,88 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use crate::codegen::BATCH_FUNC_ID;
+use core::marker::PhantomData;
+use ink_env::chain_extension::ChainExtensionMethod;
+use ink_prelude::vec::Vec;
+use scale::{
+    Decode,
+    Encode,
+};
+
+/// Collects calls of the chain extension's methods to execute them in one call.
+pub struct BatchBuilder {
+    extension_id: u16,
+    calls: Vec<(u16, Vec<u8>)>,
+}
+
+impl BatchBuilder {
+    pub fn new(extension_id: u16) -> Self {
+        BatchBuilder {
+            extension_id,
+            calls: Vec::new(),
+        }
+    }
+
+    /// Adds the call of the method with `func_id` to the batch. The returned handle
+    /// is used to get the typed result from the [`BatchOutput`].
+    pub fn push<I: Encode, O: Decode>(&mut self, func_id: u16, input: &I) -> BatchHandle<O> {
+        let index = self.calls.len();
+        self.calls.push((func_id, input.encode()));
+        BatchHandle {
+            index,
+            _output: PhantomData,
+        }
+    }
+
+    /// Executes all calls of the batch in order.
+    pub fn call(self) -> BatchOutput {
+        let id = ((self.extension_id as u32) << 16) | (BATCH_FUNC_ID as u32);
+        let results = ChainExtensionMethod::build(id)
+            .input::<Vec<(u16, Vec<u8>)>>()
+            .output::<Vec<Vec<u8>>>()
+            .ignore_error_code()
+            .call(&self.calls);
+        BatchOutput { results }
+    }
+}
+
+/// The handle of the call in the batch.
+pub struct BatchHandle<O> {
+    index: usize,
+    _output: PhantomData<fn() -> O>,
+}
+
+/// Encoded results of the batch call.
+pub struct BatchOutput {
+    results: Vec<Vec<u8>>,
+}
+
+impl BatchOutput {
+    /// Decodes the result of the call associated with the `handle`.
+    pub fn get<O: Decode>(&self, handle: &BatchHandle<O>) -> Result<O, ink_env::Error> {
+        let result = self
+            .results
+            .get(handle.index)
+            .ok_or(scale::Error::from("missing result of the batch call"))?;
+        Ok(O::decode(&mut result.as_slice())?)
+    }
+}
@@ -0,0 +1,76 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use core::marker::PhantomData;
+use ink_lang::ChainExtensionInstance;
+
+/// The chain extension of the environment generated by [`ink_environment!`](crate::ink_environment).
+///
+/// It implements all OBCE definitions listed in the macro, so the methods of the
+/// definitions are available via `self.env().extension()`.
+pub struct Extensions<Env>(PhantomData<fn() -> Env>);
+
+impl<Env> ChainExtensionInstance for Extensions<Env> {
+    type Instance = Self;
+
+    fn instantiate() -> Self::Instance {
+        Extensions(PhantomData)
+    }
+}
+
+/// Generates the custom `ink_env::Environment` with the chain extension that
+/// aggregates OBCE definitions. All other types are the same as in the
+/// `ink_env::DefaultEnvironment`.
+///
+/// ```ignore
+/// obce::ink_environment!(pub MyEnv: AssetsExt, DexExt);
+///
+/// #[ink_lang::contract(env = MyEnv)]
+/// mod contract {
+///     // ...
+///     self.env().extension().transfer(..);
+/// }
+/// ```
+#[macro_export]
+macro_rules! ink_environment {
+    ( $vis:vis $name:ident $(: $( $definition:path ),+ $(,)? )? ) => {
+        #[derive(Debug, Clone, PartialEq, Eq, $crate::ink::scale_info::TypeInfo)]
+        #[scale_info(crate = $crate::ink::scale_info)]
+        $vis enum $name {}
+
+        impl $crate::ink::ink_env::Environment for $name {
+            const MAX_EVENT_TOPICS: usize =
+                <$crate::ink::ink_env::DefaultEnvironment as $crate::ink::ink_env::Environment>::MAX_EVENT_TOPICS;
+
+            type AccountId = <$crate::ink::ink_env::DefaultEnvironment as $crate::ink::ink_env::Environment>::AccountId;
+            type Balance = <$crate::ink::ink_env::DefaultEnvironment as $crate::ink::ink_env::Environment>::Balance;
+            type Hash = <$crate::ink::ink_env::DefaultEnvironment as $crate::ink::ink_env::Environment>::Hash;
+            type BlockNumber = <$crate::ink::ink_env::DefaultEnvironment as $crate::ink::ink_env::Environment>::BlockNumber;
+            type Timestamp = <$crate::ink::ink_env::DefaultEnvironment as $crate::ink::ink_env::Environment>::Timestamp;
+
+            type ChainExtension = $crate::ink::Extensions<$name>;
+        }
+
+        $($(
+            impl $definition for $crate::ink::Extensions<$name> {}
+        )+)?
+    };
+}
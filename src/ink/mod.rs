@@ -19,6 +19,16 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+mod batch;
+mod environment;
+mod output;
+
+pub use batch::{
+    BatchBuilder,
+    BatchHandle,
+    BatchOutput,
+};
+pub use environment::Extensions;
 #[cfg(feature = "std")]
 pub use ink_engine;
 pub use ink_env;
@@ -28,3 +38,5 @@ pub use ink_metadata;
 pub use ink_prelude;
 pub use ink_primitives;
 pub use ink_storage;
+pub use output::EncodedOutput;
+pub use scale_info;
@@ -0,0 +1,51 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use ink_prelude::vec::Vec;
+use scale::{
+    Decode,
+    DecodeAll,
+    Input,
+};
+
+/// The encoded output of the chain extension's method.
+///
+/// It takes the whole output buffer without decoding, so the output can be decoded
+/// later without panicking if the chain extension returned something unexpected.
+pub struct EncodedOutput(pub Vec<u8>);
+
+impl EncodedOutput {
+    /// Decodes the output as `O`. The whole output should be consumed.
+    pub fn decode_as<O: Decode>(&self) -> Result<O, ink_env::Error> {
+        Ok(O::decode_all(&mut self.0.as_slice())?)
+    }
+}
+
+impl Decode for EncodedOutput {
+    fn decode<I: Input>(input: &mut I) -> Result<Self, scale::Error> {
+        let len = input
+            .remaining_len()?
+            .ok_or("the length of the chain extension's output is unknown")?;
+        let mut output = ink_prelude::vec![0; len];
+        input.read(&mut output)?;
+        Ok(EncodedOutput(output))
+    }
+}
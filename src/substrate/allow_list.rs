@@ -0,0 +1,92 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use frame_support::{
+    traits::Get,
+    weights::Weight,
+};
+use frame_system::Config as SysConfig;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+/// The provider decides which contracts are allowed to call the chain extension.
+///
+/// It is used by `#[obce::definition(allow = Provider)]` and `#[obce::implementation(allow = Provider)]`
+/// to reject calls from other contracts before any method logic runs.
+pub trait AllowListProvider<T: SysConfig> {
+    /// Returns `true` if the contract with `address` and `code_hash` can call the chain extension.
+    fn is_allowed(address: &T::AccountId, code_hash: &T::Hash) -> bool;
+
+    /// The weight of [`AllowListProvider::is_allowed`], by default one storage read.
+    fn weight() -> Weight {
+        T::DbWeight::get().reads(1)
+    }
+}
+
+/// The allow list of the chain extension declared by `#[obce::definition(allow = Provider)]`.
+///
+/// The definition implements it for every runtime and the dispatcher generated by `#[obce::implementation]`
+/// checks it before any method logic runs. Without `allow` all contracts can call the chain extension.
+pub trait ExtensionAllowList<T: pallet_contracts::Config> {
+    /// Returns `DispatchError::Other("CallerNotAllowed")` if the calling contract is not allowed.
+    fn ensure_allowed<E>(_env: &mut Environment<E, BufInBufOutState>) -> Result<(), DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        Ok(())
+    }
+}
+
+/// Returns `DispatchError::Other("CallerNotAllowed")` if the calling contract
+/// is not allowed by the `Provider`.
+///
+/// The lookup of the code hash and the weight of the `Provider` are charged before the check.
+pub fn ensure_allowed<Provider, E>(env: &mut Environment<E, BufInBufOutState>) -> Result<(), DispatchError>
+where
+    E: Ext,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    Provider: AllowListProvider<E::T>,
+{
+    env.charge_weight(
+        <E::T as SysConfig>::DbWeight::get()
+            .reads(1)
+            .saturating_add(Provider::weight()),
+    )?;
+
+    let ext = env.ext();
+    let address = ext.address();
+    let allowed = ext
+        .code_hash(address)
+        .map(|code_hash| Provider::is_allowed(address, &code_hash))
+        .unwrap_or(false);
+
+    if allowed {
+        Ok(())
+    } else {
+        Err(DispatchError::Other("CallerNotAllowed"))
+    }
+}
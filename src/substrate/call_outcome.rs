@@ -0,0 +1,116 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use crate::substrate::CriticalError;
+
+/// The outcome of the chain extension method call.
+#[derive(Clone, Copy, Debug, PartialEq, Eq)]
+pub enum CallOutcome {
+    /// The method returned a successful result.
+    Ok,
+    /// The method returned a non-critical error that is propagated to the caller contract.
+    Error,
+    /// The method returned a critical error that terminates the execution.
+    CriticalError,
+}
+
+pub struct ToCallOutcome<'a, T>(pub &'a T);
+
+impl<'a, T, E> ToCallOutcome<'a, Result<Result<T, E>, CriticalError>> {
+    #[inline]
+    // The same as for `ToCriticalErr`, it is the specialized case for detection.
+    #[allow(dead_code)]
+    pub fn call_outcome(&self) -> CallOutcome {
+        match self.0 {
+            Ok(Ok(_)) => CallOutcome::Ok,
+            Ok(Err(_)) => CallOutcome::Error,
+            Err(_) => CallOutcome::CriticalError,
+        }
+    }
+}
+
+pub trait ToCallOutcomeFallback {
+    fn call_outcome(&self) -> CallOutcome;
+}
+
+impl<'a, T> ToCallOutcomeFallback for ToCallOutcome<'a, Result<T, CriticalError>> {
+    #[inline]
+    fn call_outcome(&self) -> CallOutcome {
+        match self.0 {
+            Ok(_) => CallOutcome::Ok,
+            Err(_) => CallOutcome::CriticalError,
+        }
+    }
+}
+
+/// Returns the [`CallOutcome`](crate::substrate::CallOutcome) of the result
+/// produced by the `to_critical_error!` macro.
+#[macro_export]
+#[doc(hidden)]
+macro_rules! call_outcome {
+    ( $e:expr $(,)? ) => {{
+        #[allow(unused_imports)]
+        use $crate::substrate::ToCallOutcomeFallback;
+        $crate::substrate::ToCallOutcome($e).call_outcome()
+    }};
+}
+
+#[cfg(test)]
+mod tests {
+    use crate::substrate::{
+        CallOutcome,
+        CriticalError,
+        SupportCriticalError,
+    };
+
+    #[test]
+    fn call_outcome_works_for_result() {
+        #[derive(PartialEq, Eq, Debug)]
+        enum Error {
+            NonCritical,
+            Critical(CriticalError),
+        }
+
+        impl SupportCriticalError for Error {
+            fn try_to_critical(self) -> Result<CriticalError, Self> {
+                match self {
+                    Error::Critical(critical) => Ok(critical),
+                    _ => Err(self),
+                }
+            }
+        }
+
+        let result: Result<(), Error> = Ok(());
+        assert_eq!(call_outcome!(&to_critical_error!(result)), CallOutcome::Ok);
+
+        let error: Result<(), _> = Err(Error::NonCritical);
+        assert_eq!(call_outcome!(&to_critical_error!(error)), CallOutcome::Error);
+
+        let error: Result<(), _> = Err(Error::Critical(CriticalError::BadOrigin));
+        assert_eq!(call_outcome!(&to_critical_error!(error)), CallOutcome::CriticalError);
+    }
+
+    #[test]
+    fn call_outcome_works_without_result() {
+        let result = ();
+        assert_eq!(call_outcome!(&to_critical_error!(result)), CallOutcome::Ok);
+    }
+}
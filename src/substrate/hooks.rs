@@ -0,0 +1,57 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use crate::substrate::CallOutcome;
+use frame_system::Config as SysConfig;
+use pallet_contracts::chain_extension::{
+    BufInBufOutState,
+    Environment,
+    Ext,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+/// Hooks executed around each method of the chain extension.
+///
+/// They are called by the dispatcher generated with `#[obce::implementation(hooks)]`,
+/// so cross-cutting logic like metrics or the base weight is implemented once for all methods.
+pub trait ExtensionHooks<T: SysConfig> {
+    /// Called before the input of the method is decoded. The error stops the execution of the chain extension.
+    fn before_call<E>(
+        &mut self,
+        _func_id: u16,
+        _env: &mut Environment<E, BufInBufOutState>,
+    ) -> Result<(), DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        Ok(())
+    }
+
+    /// Called after the method is executed and before its output is written.
+    ///
+    /// It is called for every method that passed `before_call`, even if decoding of the input,
+    /// the reentrancy guard or the validation failed. Failures of decoding and the reentrancy guard
+    /// give `CallOutcome::CriticalError`. Invalid arguments are returned to the caller as
+    /// `InvalidArgument` error of the method, so they give `CallOutcome::Error`.
+    fn after_call(&mut self, _func_id: u16, _outcome: &CallOutcome) {}
+}
@@ -0,0 +1,49 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use crate::{
+    codegen::MethodMetadata,
+    substrate::ExtensionContext,
+};
+use frame_system::Config as SysConfig;
+use pallet_contracts::chain_extension::{
+    Ext,
+    UncheckedFrom,
+};
+use sp_runtime::DispatchError;
+
+/// Middleware around each method of the chain extension.
+///
+/// Layers are applied by `#[obce::implementation(layers(First, Second))]`, where `First` is the outermost.
+/// The innermost `next` decodes the input, executes the method, and writes its output. The layer can
+/// execute the logic before and after `next`, or return an error without calling it.
+pub trait Layer<T: SysConfig> {
+    fn call<'a, 'b, E, Extension, F>(
+        context: &mut ExtensionContext<'a, 'b, E, T, Extension>,
+        extension_id: u16,
+        method: MethodMetadata,
+        next: F,
+    ) -> Result<(), DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+        F: FnOnce(&mut ExtensionContext<'a, 'b, E, T, Extension>) -> Result<(), DispatchError>;
+}
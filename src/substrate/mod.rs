@@ -19,24 +19,51 @@
 // OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
 // WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
 
+mod allow_list;
+mod call_outcome;
+mod hooks;
 mod is_critical_error;
+mod layer;
+mod reentrancy;
 
+pub mod pallet;
+
+pub use allow_list::{
+    ensure_allowed,
+    AllowListProvider,
+    ExtensionAllowList,
+};
+pub use call_outcome::{
+    CallOutcome,
+    ToCallOutcome,
+    ToCallOutcomeFallback,
+};
 pub use frame_support;
 pub use frame_system;
+pub use hooks::ExtensionHooks;
 pub use is_critical_error::{
     ToCriticalErr,
     ToCriticalErrFallback,
 };
+pub use layer::Layer;
+pub use log;
 pub use pallet_contracts;
+pub use reentrancy::ReentrancyGuard;
 pub use sp_core;
 pub use sp_runtime;
 pub use sp_std;
 
+use frame_support::storage::{
+    with_transaction,
+    TransactionOutcome,
+};
 use frame_system::Config as SysConfig;
 use pallet_contracts::chain_extension::{
     BufInBufOutState,
     Environment,
     Ext,
+    InitState,
+    RetVal,
     UncheckedFrom,
 };
 use sp_runtime::DispatchError;
@@ -62,10 +89,42 @@ where
     }
 }
 
+/// Dispatcher of methods of the `Definition` trait implemented by the chain extension.
+///
+/// It is generated by `#[obce::implementation]` for each implemented trait, so several
+/// traits can be served by one chain extension that routes calls by the extension id.
+pub trait ExtensionDispatch<T: pallet_contracts::Config, Definition: ?Sized> {
+    fn dispatch<E>(&mut self, env: Environment<E, InitState>) -> Result<RetVal, DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>;
+}
+
 pub type CriticalError = DispatchError;
 
+/// The maximum nesting of the decoded input. The same as in `pallet-contracts`.
+pub const MAX_DECODE_NESTING: u32 = 256;
+
 /// The trait allows filtering error on critical and non. Critical errors terminate the execution
 /// of the chain extension. Non-critical errors are propagated to the caller contract via buffer.
 pub trait SupportCriticalError: Sized {
     fn try_to_critical(self) -> Result<CriticalError, Self>;
 }
+
+/// Executes `f` in the storage layer that is always rolled back,
+/// so the state is not modified by the execution.
+pub fn with_rollback<R>(f: impl FnOnce() -> R) -> Result<R, DispatchError> {
+    with_transaction(|| TransactionOutcome::Rollback(Ok(f())))
+}
+
+/// Executes `f` in the storage layer that is committed only if the outcome of the call is
+/// [`CallOutcome::Ok`], so the state is not modified if the call returns an error.
+pub fn transactional<R>(f: impl FnOnce() -> (R, CallOutcome)) -> Result<R, DispatchError> {
+    with_transaction(|| {
+        let (result, outcome) = f();
+        match outcome {
+            CallOutcome::Ok => TransactionOutcome::Commit(Ok(result)),
+            _ => TransactionOutcome::Rollback(Ok(result)),
+        }
+    })
+}
@@ -0,0 +1,91 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! The pallet allows disabling methods of chain extensions without a runtime upgrade.
+//!
+//! Chain extensions implemented with `#[obce::implementation(kill_switch)]` reject
+//! calls of methods disabled in this pallet before decoding the input.
+
+pub use pallet::*;
+
+#[frame_support::pallet]
+pub mod pallet {
+    use frame_support::pallet_prelude::*;
+    use frame_system::pallet_prelude::*;
+
+    #[pallet::pallet]
+    pub struct Pallet<T>(_);
+
+    #[pallet::config]
+    pub trait Config: frame_system::Config {
+        type RuntimeEvent: From<Event<Self>> + IsType<<Self as frame_system::Config>::RuntimeEvent>;
+
+        /// The origin that is allowed to disable and enable methods.
+        type KillSwitchOrigin: EnsureOrigin<OriginFor<Self>>;
+    }
+
+    /// Disabled methods as `(extension_id, func_id)` pairs.
+    #[pallet::storage]
+    pub type DisabledMethods<T> = StorageMap<_, Twox64Concat, (u16, u16), (), OptionQuery>;
+
+    #[pallet::event]
+    #[pallet::generate_deposit(pub(super) fn deposit_event)]
+    pub enum Event<T: Config> {
+        /// The method of the chain extension was disabled.
+        MethodDisabled { extension_id: u16, func_id: u16 },
+        /// The method of the chain extension was enabled.
+        MethodEnabled { extension_id: u16, func_id: u16 },
+    }
+
+    #[pallet::call]
+    impl<T: Config> Pallet<T> {
+        /// Disables the method with `func_id` of the chain extension with `extension_id`.
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn disable_method(origin: OriginFor<T>, extension_id: u16, func_id: u16) -> DispatchResult {
+            T::KillSwitchOrigin::ensure_origin(origin)?;
+            DisabledMethods::<T>::insert((extension_id, func_id), ());
+            Self::deposit_event(Event::MethodDisabled { extension_id, func_id });
+            Ok(())
+        }
+
+        /// Enables the method with `func_id` of the chain extension with `extension_id`.
+        #[pallet::weight(T::DbWeight::get().writes(1))]
+        pub fn enable_method(origin: OriginFor<T>, extension_id: u16, func_id: u16) -> DispatchResult {
+            T::KillSwitchOrigin::ensure_origin(origin)?;
+            DisabledMethods::<T>::remove((extension_id, func_id));
+            Self::deposit_event(Event::MethodEnabled { extension_id, func_id });
+            Ok(())
+        }
+    }
+
+    impl<T: Config> Pallet<T> {
+        /// Returns `true` if the method with `func_id` of the chain extension
+        /// with `extension_id` is disabled.
+        pub fn is_disabled(extension_id: u16, func_id: u16) -> bool {
+            DisabledMethods::<T>::contains_key((extension_id, func_id))
+        }
+
+        /// The weight of [`Pallet::is_disabled`]. The chain extension charges it before each check.
+        pub fn is_disabled_weight() -> Weight {
+            T::DbWeight::get().reads(1)
+        }
+    }
+}
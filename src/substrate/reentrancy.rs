@@ -0,0 +1,103 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+use sp_runtime::DispatchError;
+use sp_std::{
+    vec,
+    vec::Vec,
+};
+
+// Ids of chain extensions whose guarded methods are executing now.
+environmental::environmental!(executing: Vec<u16>);
+
+/// The guard rejects nested calls into guarded methods of the chain extension.
+///
+/// All guarded methods of the chain extension share the guard, so a guarded method can't be
+/// reached from another one through a contract either. The executing methods are tracked outside
+/// of the storage, so the guard doesn't touch the state and its lifetime is bound to the execution
+/// of the method.
+pub struct ReentrancyGuard;
+
+impl ReentrancyGuard {
+    /// Executes `f` as the guarded method of the chain extension with `extension_id`.
+    ///
+    /// Returns `DispatchError::Other("ReentrantCall")` if any guarded method of the chain extension
+    /// is already executing, for example, if it called a contract that called the chain extension
+    /// again. Unguarded methods and guarded methods of other chain extensions can be called from
+    /// the guarded one.
+    pub fn execute<R>(extension_id: u16, f: impl FnOnce() -> Result<R, DispatchError>) -> Result<R, DispatchError> {
+        let entered = executing::with(|executing| {
+            if executing.contains(&extension_id) {
+                return Err(DispatchError::Other("ReentrantCall"))
+            }
+            executing.push(extension_id);
+            Ok(())
+        });
+
+        match entered {
+            Some(entered) => {
+                entered?;
+                let result = f();
+                executing::with(|executing| executing.retain(|executing| executing != &extension_id));
+                result
+            }
+            // The outermost guarded method starts tracking of executing chain extensions.
+            None => executing::using(&mut vec![extension_id], f),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ReentrancyGuard;
+    use sp_runtime::DispatchError;
+
+    #[test]
+    fn nested_call_of_the_same_extension_is_rejected() {
+        let result = ReentrancyGuard::execute(1, || ReentrancyGuard::execute(1, || Ok(())));
+        assert_eq!(result, Err(DispatchError::Other("ReentrantCall")));
+    }
+
+    #[test]
+    fn nested_call_of_another_extension_is_allowed() {
+        let result = ReentrancyGuard::execute(1, || ReentrancyGuard::execute(2, || Ok(13)));
+        assert_eq!(result, Ok(13));
+    }
+
+    #[test]
+    fn extension_can_be_called_again_after_exit() {
+        let result = ReentrancyGuard::execute(1, || {
+            ReentrancyGuard::execute(2, || Ok(()))?;
+            ReentrancyGuard::execute(2, || Ok(()))
+        });
+        assert_eq!(result, Ok(()));
+        assert_eq!(ReentrancyGuard::execute(1, || Ok(())), Ok(()));
+    }
+
+    #[test]
+    fn guard_is_released_after_error() {
+        let result = ReentrancyGuard::execute(1, || {
+            let _ = ReentrancyGuard::execute(2, || Err::<(), _>(DispatchError::Other("Failed")));
+            ReentrancyGuard::execute(2, || Ok(()))
+        });
+        assert_eq!(result, Ok(()));
+    }
+}
@@ -0,0 +1,43 @@
+;; Calls the chain extension with the input of the contract and returns its status code and output.
+;;
+;; The input is `(func_id: u32, input)` and the output is `(status: u32, output)`.
+(module
+	(import "seal0" "seal_input" (func $seal_input (param i32 i32)))
+	(import "seal0" "seal_call_chain_extension"
+		(func $seal_call_chain_extension (param i32 i32 i32 i32 i32) (result i32)))
+	(import "seal0" "seal_return" (func $seal_return (param i32 i32 i32)))
+	(import "env" "memory" (memory 1 1))
+
+	;; [0, 4) the length of the input buffer
+	(data (i32.const 0) "\00\70")
+
+	;; [4, 8) the length of the output buffer
+	(data (i32.const 4) "\00\70")
+
+	;; [16, 0x7010) the input buffer: `func_id` and the input of the method
+
+	;; [0x8000, 0x8004) the status code
+
+	;; [0x8004, 0xf004) the output buffer
+
+	(func (export "deploy"))
+
+	(func (export "call")
+		(call $seal_input (i32.const 16) (i32.const 0))
+		(i32.store
+			(i32.const 0x8000)
+			(call $seal_call_chain_extension
+				(i32.load (i32.const 16))
+				(i32.const 20)
+				(i32.sub (i32.load (i32.const 0)) (i32.const 4))
+				(i32.const 0x8004)
+				(i32.const 4)
+			)
+		)
+		(call $seal_return
+			(i32.const 0)
+			(i32.const 0x8000)
+			(i32.add (i32.load (i32.const 4)) (i32.const 4))
+		)
+	)
+)
@@ -0,0 +1,423 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Minimal runtime with `pallet-contracts` to test chain extensions.
+//!
+//! The runtime is created by [`construct_test_runtime`] in the crate of the test, so any chain
+//! extension can be plugged into it. The crate that calls the macro should depend on
+//! `frame-support`, `parity-scale-codec` and `scale-info`, because `construct_runtime!` requires them.
+//!
+//! Contracts call the chain extension via the [`PROXY`] fixture, see [`with_proxy`] and [`Proxy`].
+//! With the `ink` feature, calls of the ink! side are dispatched into the runtime by [`register_loopback`].
+
+#[cfg(feature = "ink")]
+mod loopback;
+
+#[cfg(feature = "ink")]
+pub use loopback::register_loopback;
+
+pub use frame_support;
+pub use frame_system;
+pub use obce;
+pub use pallet_balances;
+pub use pallet_contracts;
+pub use pallet_contracts_primitives;
+pub use pallet_randomness_collective_flip;
+pub use pallet_timestamp;
+pub use sp_core;
+pub use sp_io;
+pub use sp_runtime;
+
+use frame_support::{
+    traits::GenesisBuild,
+    weights::Weight,
+};
+use pallet_contracts_primitives::{
+    Code,
+    ExecReturnValue,
+};
+use scale::{
+    Decode,
+    Encode,
+};
+use sp_core::crypto::{
+    AccountId32,
+    UncheckedFrom,
+};
+use sp_io::TestExternalities;
+use sp_runtime::DispatchError;
+use std::path::Path;
+
+pub const ALICE: AccountId32 = AccountId32::new([1; 32]);
+pub const BOB: AccountId32 = AccountId32::new([2; 32]);
+
+/// The balance of [`ALICE`] in [`with_proxy`].
+pub const ENDOWMENT: u128 = 1_000_000_000_000;
+
+/// The gas limit that is enough for any call in tests.
+pub const GAS_LIMIT: Weight = Weight::from_ref_time(100_000_000_000);
+
+/// The contract that calls the chain extension with its input and returns the status code and the output.
+///
+/// The input of the contract is `(func_id: u32, input)`, where `func_id` includes the extension id
+/// in the upper 16 bits. The output is `(status: u32, output)`.
+pub const PROXY: &str = include_str!("../fixtures/proxy.wat");
+
+/// Creates the runtime `$runtime` with `pallet-contracts` that uses `$extension` as the chain extension.
+///
+/// The macro declares helper types like `BlockWeights` and `Schedule` next to the runtime,
+/// so it is better to call it in a separate module. With `kill_switch` the runtime also includes
+/// `obce::substrate::pallet` as `KillSwitch`, methods are disabled and enabled by the root origin.
+///
+/// ```ignore
+/// mod runtime {
+///     obce_test_runtime::construct_test_runtime!(Runtime, crate::MyExtension);
+/// }
+///
+/// mod runtime_with_kill_switch {
+///     obce_test_runtime::construct_test_runtime!(Runtime, crate::MyExtension, kill_switch);
+/// }
+/// ```
+#[macro_export]
+macro_rules! construct_test_runtime {
+    ($runtime:ident, $extension:ty $(,)?) => {
+        $crate::construct_test_runtime!(@runtime $runtime, $extension, {});
+    };
+    ($runtime:ident, $extension:ty, kill_switch $(,)?) => {
+        $crate::construct_test_runtime!(@runtime $runtime, $extension, {
+            KillSwitch: $crate::obce::substrate::pallet::{Pallet, Call, Storage, Event<T>},
+        });
+
+        impl $crate::obce::substrate::pallet::Config for $runtime {
+            type RuntimeEvent = RuntimeEvent;
+            type KillSwitchOrigin = $crate::frame_system::EnsureRoot<$crate::sp_core::crypto::AccountId32>;
+        }
+    };
+    (@runtime $runtime:ident, $extension:ty, { $($pallets:tt)* }) => {
+        type UncheckedExtrinsic = $crate::frame_system::mocking::MockUncheckedExtrinsic<$runtime>;
+        type Block = $crate::frame_system::mocking::MockBlock<$runtime>;
+
+        $crate::frame_support::construct_runtime!(
+            pub enum $runtime where
+                Block = Block,
+                NodeBlock = Block,
+                UncheckedExtrinsic = UncheckedExtrinsic,
+            {
+                System: $crate::frame_system::{Pallet, Call, Config, Storage, Event<T>},
+                Balances: $crate::pallet_balances::{Pallet, Call, Storage, Config<T>, Event<T>},
+                Timestamp: $crate::pallet_timestamp::{Pallet, Call, Storage, Inherent},
+                RandomnessCollectiveFlip: $crate::pallet_randomness_collective_flip::{Pallet, Storage},
+                Contracts: $crate::pallet_contracts::{Pallet, Call, Storage, Event<T>},
+                $($pallets)*
+            }
+        );
+
+        $crate::frame_support::parameter_types! {
+            pub const BlockHashCount: u64 = 250;
+            pub BlockWeights: $crate::frame_system::limits::BlockWeights =
+                $crate::frame_system::limits::BlockWeights::simple_max(
+                    $crate::frame_support::weights::Weight::from_ref_time(2_000_000_000_000),
+                );
+            pub const ExistentialDeposit: u128 = 1;
+            pub const MinimumPeriod: u64 = 1;
+            pub const DepositPerByte: u128 = 1;
+            pub const DepositPerItem: u128 = 2;
+            pub DeletionWeightLimit: $crate::frame_support::weights::Weight =
+                $crate::frame_support::weights::Weight::from_ref_time(500_000_000_000);
+            pub Schedule: $crate::pallet_contracts::Schedule<$runtime> = ::core::default::Default::default();
+        }
+
+        impl $crate::frame_system::Config for $runtime {
+            type BaseCallFilter = $crate::frame_support::traits::Everything;
+            type BlockWeights = BlockWeights;
+            type BlockLength = ();
+            type DbWeight = ();
+            type Origin = Origin;
+            type Index = u64;
+            type BlockNumber = u64;
+            type Hash = $crate::sp_core::H256;
+            type RuntimeCall = RuntimeCall;
+            type Hashing = $crate::sp_runtime::traits::BlakeTwo256;
+            type AccountId = $crate::sp_core::crypto::AccountId32;
+            type Lookup = $crate::sp_runtime::traits::IdentityLookup<Self::AccountId>;
+            type Header = $crate::sp_runtime::testing::Header;
+            type RuntimeEvent = RuntimeEvent;
+            type BlockHashCount = BlockHashCount;
+            type Version = ();
+            type PalletInfo = PalletInfo;
+            type AccountData = $crate::pallet_balances::AccountData<u128>;
+            type OnNewAccount = ();
+            type OnKilledAccount = ();
+            type SystemWeightInfo = ();
+            type SS58Prefix = ();
+            type OnSetCode = ();
+            type MaxConsumers = $crate::frame_support::traits::ConstU32<16>;
+        }
+
+        impl $crate::pallet_balances::Config for $runtime {
+            type MaxLocks = ();
+            type MaxReserves = ();
+            type ReserveIdentifier = [u8; 8];
+            type Balance = u128;
+            type RuntimeEvent = RuntimeEvent;
+            type DustRemoval = ();
+            type ExistentialDeposit = ExistentialDeposit;
+            type AccountStore = System;
+            type WeightInfo = ();
+        }
+
+        impl $crate::pallet_timestamp::Config for $runtime {
+            type Moment = u64;
+            type OnTimestampSet = ();
+            type MinimumPeriod = MinimumPeriod;
+            type WeightInfo = ();
+        }
+
+        impl $crate::pallet_randomness_collective_flip::Config for $runtime {}
+
+        impl $crate::sp_runtime::traits::Convert<$crate::frame_support::weights::Weight, u128> for $runtime {
+            fn convert(weight: $crate::frame_support::weights::Weight) -> u128 {
+                weight.ref_time() as u128
+            }
+        }
+
+        impl $crate::pallet_contracts::Config for $runtime {
+            type Time = Timestamp;
+            type Randomness = RandomnessCollectiveFlip;
+            type Currency = Balances;
+            type RuntimeEvent = RuntimeEvent;
+            type RuntimeCall = RuntimeCall;
+            type CallFilter = $crate::frame_support::traits::Nothing;
+            type CallStack = [$crate::pallet_contracts::Frame<Self>; 31];
+            type WeightPrice = Self;
+            type WeightInfo = ();
+            type ChainExtension = $extension;
+            type DeletionQueueDepth = $crate::frame_support::traits::ConstU32<1024>;
+            type DeletionWeightLimit = DeletionWeightLimit;
+            type Schedule = Schedule;
+            type DepositPerByte = DepositPerByte;
+            type DepositPerItem = DepositPerItem;
+            type AddressGenerator = $crate::pallet_contracts::DefaultAddressGenerator;
+            type ContractAccessWeight = $crate::pallet_contracts::DefaultContractAccessWeight<BlockWeights>;
+            type MaxCodeLen = $crate::frame_support::traits::ConstU32<{ 128 * 1024 }>;
+            type RelaxedMaxCodeLen = $crate::frame_support::traits::ConstU32<{ 256 * 1024 }>;
+            type MaxStorageKeyLen = $crate::frame_support::traits::ConstU32<128>;
+        }
+    };
+}
+
+/// Creates the externalities with `balances` at the genesis and the first block.
+pub fn new_test_ext<T: pallet_balances::Config>(balances: Vec<(T::AccountId, T::Balance)>) -> TestExternalities {
+    let mut storage = frame_system::GenesisConfig::default()
+        .build_storage::<T>()
+        .expect("genesis of `frame_system` is valid");
+    pallet_balances::GenesisConfig::<T> { balances }
+        .assimilate_storage(&mut storage)
+        .expect("genesis of `pallet_balances` is valid");
+
+    let mut ext = TestExternalities::new(storage);
+    ext.execute_with(|| frame_system::Pallet::<T>::set_block_number(1u32.into()));
+    ext
+}
+
+/// Compiles the WAT fixture into the Wasm module.
+pub fn wat_to_wasm(wat: &str) -> Vec<u8> {
+    wat::parse_str(wat).unwrap_or_else(|error| panic!("unable to compile the fixture: {}", error))
+}
+
+/// Loads the Wasm module from the `.wat` or `.wasm` file.
+pub fn load_module<P: AsRef<Path>>(path: P) -> Vec<u8> {
+    wat::parse_file(path.as_ref())
+        .unwrap_or_else(|error| panic!("unable to load the fixture {}: {}", path.as_ref().display(), error))
+}
+
+/// Uploads the `code` on behalf of the `owner` and returns its hash.
+pub fn upload_code<T>(owner: T::AccountId, code: Vec<u8>) -> T::Hash
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    pallet_contracts::Pallet::<T>::bare_upload_code(owner, code, None)
+        .unwrap_or_else(|error| panic!("unable to upload the code: {:?}", error))
+        .code_hash
+}
+
+/// Instantiates the contract from the `code` with the constructor's `input` and returns its address.
+pub fn instantiate<T>(owner: T::AccountId, code: Vec<u8>, input: Vec<u8>) -> T::AccountId
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    let result = pallet_contracts::Pallet::<T>::bare_instantiate(
+        owner,
+        Default::default(),
+        GAS_LIMIT,
+        None,
+        Code::Upload(code.into()),
+        input,
+        Vec::new(),
+        true,
+    );
+    let output = result
+        .result
+        .unwrap_or_else(|error| panic!("unable to instantiate the contract: {:?}", error));
+    assert!(
+        !output.result.did_revert(),
+        "the constructor is reverted: {:?}",
+        output.result.data
+    );
+    output.account_id
+}
+
+/// Calls the `contract` with the `input` on behalf of the `origin`.
+pub fn call<T>(origin: T::AccountId, contract: T::AccountId, input: Vec<u8>) -> ExecReturnValue
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    pallet_contracts::Pallet::<T>::bare_call(origin, contract, Default::default(), GAS_LIMIT, None, input, true)
+        .result
+        .unwrap_or_else(|error| panic!("unable to call the contract: {:?}", error))
+}
+
+/// Instantiates the [`PROXY`] contract on behalf of the `owner` and returns its address.
+pub fn deploy_proxy<T>(owner: T::AccountId) -> T::AccountId
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    instantiate::<T>(owner, wat_to_wasm(PROXY), Vec::new())
+}
+
+/// Calls the method `func_id` of the chain extension with `extension_id` via the `proxy` contract
+/// on behalf of the `origin`.
+///
+/// Returns the status code and the output of the method, the output is empty if the status code
+/// is not zero. Critical errors of the chain extension are returned as `Err`.
+pub fn call_extension<T>(
+    origin: T::AccountId,
+    proxy: T::AccountId,
+    extension_id: u16,
+    func_id: u16,
+    input: Vec<u8>,
+) -> Result<(u32, Vec<u8>), DispatchError>
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    let data = proxy_input(extension_id, func_id, input);
+    let output =
+        pallet_contracts::Pallet::<T>::bare_call(origin, proxy, Default::default(), GAS_LIMIT, None, data, true)
+            .result?;
+    let (status, output) = output.data.split_at(4);
+    let status = u32::from_le_bytes(status.try_into().expect("the status code is 4 bytes"));
+    // The chain extension doesn't write the output if it returns the status code.
+    let output = if status == 0 { output.to_vec() } else { Vec::new() };
+    Ok((status, output))
+}
+
+/// Calls the method like [`call_extension`] and returns the gas consumed by the whole call,
+/// including critical errors.
+pub fn extension_gas_consumed<T>(
+    origin: T::AccountId,
+    proxy: T::AccountId,
+    extension_id: u16,
+    func_id: u16,
+    input: Vec<u8>,
+) -> Weight
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    let data = proxy_input(extension_id, func_id, input);
+    pallet_contracts::Pallet::<T>::bare_call(origin, proxy, Default::default(), GAS_LIMIT, None, data, true)
+        .gas_consumed
+}
+
+/// Encodes the input of the [`PROXY`], the `func_id` of the chain extension includes the `extension_id`.
+fn proxy_input(extension_id: u16, func_id: u16, input: Vec<u8>) -> Vec<u8> {
+    let mut data = (((extension_id as u32) << 16) | (func_id as u32))
+        .to_le_bytes()
+        .to_vec();
+    data.extend(input);
+    data
+}
+
+/// Executes `f` in new externalities where [`ALICE`] has [`ENDOWMENT`] and has deployed the [`PROXY`].
+///
+/// ```ignore
+/// with_proxy::<Runtime, _>(|proxy| {
+///     assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 21u32), Ok(42));
+/// });
+/// ```
+pub fn with_proxy<T, R>(f: impl FnOnce(Proxy<T>) -> R) -> R
+where
+    T: pallet_contracts::Config
+        + pallet_balances::Config<Balance = u128>
+        + frame_system::Config<AccountId = AccountId32>,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    new_test_ext::<T>(vec![(ALICE, ENDOWMENT)]).execute_with(|| f(Proxy::deploy(ALICE)))
+}
+
+/// The [`PROXY`] contract that calls the chain extension on behalf of its owner.
+pub struct Proxy<T: frame_system::Config> {
+    pub owner: T::AccountId,
+    pub address: T::AccountId,
+}
+
+impl<T> Proxy<T>
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    /// Instantiates the proxy on behalf of the `owner`.
+    pub fn deploy(owner: T::AccountId) -> Self {
+        let address = deploy_proxy::<T>(owner.clone());
+        Self { owner, address }
+    }
+
+    /// Calls the method `func_id` of the chain extension with `extension_id` with the encoded `input`,
+    /// see [`call_extension`].
+    pub fn call(&self, extension_id: u16, func_id: u16, input: Vec<u8>) -> Result<(u32, Vec<u8>), DispatchError> {
+        call_extension::<T>(self.owner.clone(), self.address.clone(), extension_id, func_id, input)
+    }
+
+    /// Calls the method `func_id` like [`Proxy::call`] and returns the consumed gas,
+    /// see [`extension_gas_consumed`].
+    pub fn gas_consumed(&self, extension_id: u16, func_id: u16, input: Vec<u8>) -> Weight {
+        extension_gas_consumed::<T>(self.owner.clone(), self.address.clone(), extension_id, func_id, input)
+    }
+
+    /// Calls the method that doesn't return the status code and decodes its output as `O`.
+    ///
+    /// Panics if the status code is not zero or the output is not `O`.
+    pub fn call_method<O: Decode>(
+        &self,
+        extension_id: u16,
+        func_id: u16,
+        input: impl Encode,
+    ) -> Result<O, DispatchError> {
+        let (status, output) = self.call(extension_id, func_id, input.encode())?;
+        assert_eq!(status, 0, "the method returned the status code");
+        Ok(O::decode(&mut &output[..]).unwrap_or_else(|error| panic!("unable to decode the output: {}", error)))
+    }
+}
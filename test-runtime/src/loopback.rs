@@ -0,0 +1,122 @@
+// Copyright (c) 2012-2022 Supercolony
+//
+// Permission is hereby granted, free of charge, to any person obtaining
+// a copy of this software and associated documentation files (the"Software"),
+// to deal in the Software without restriction, including
+// without limitation the rights to use, copy, modify, merge, publish,
+// distribute, sublicense, and/or sell copies of the Software, and to
+// permit persons to whom the Software is furnished to do so, subject to
+// the following conditions:
+//
+// The above copyright notice and this permission notice shall be
+// included in all copies or substantial portions of the Software.
+//
+// THE SOFTWARE IS PROVIDED "AS IS", WITHOUT WARRANTY OF ANY KIND,
+// EXPRESS OR IMPLIED, INCLUDING BUT NOT LIMITED TO THE WARRANTIES OF
+// MERCHANTABILITY, FITNESS FOR A PARTICULAR PURPOSE AND
+// NONINFRINGEMENT. IN NO EVENT SHALL THE AUTHORS OR COPYRIGHT HOLDERS BE
+// LIABLE FOR ANY CLAIM, DAMAGES OR OTHER LIABILITY, WHETHER IN AN ACTION
+// OF CONTRACT, TORT OR OTHERWISE, ARISING FROM, OUT OF OR IN CONNECTION
+// WITH THE SOFTWARE OR THE USE OR OTHER DEALINGS IN THE SOFTWARE.
+
+//! Dispatches calls of the ink! side into the chain extension of the test runtime.
+
+use crate::{
+    call_extension,
+    deploy_proxy,
+};
+use obce::{
+    codegen::{
+        ExtensionDescription,
+        BATCH_FUNC_ID,
+    },
+    ink::ink_env::test::{
+        register_chain_extension,
+        ChainExtension,
+    },
+};
+use sp_core::crypto::UncheckedFrom;
+use sp_io::TestExternalities;
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+/// Registers methods of the `Definition` and its batch call in the off-chain ink! engine.
+///
+/// Each call of the ink! side is executed by the [`PROXY`](crate::PROXY) contract deployed on behalf of
+/// the `origin` in the `ext`, so the input is decoded and the output is encoded by the dispatcher generated
+/// with `#[obce::implementation]`. Critical errors of the chain extension panic, because they terminate
+/// the contract on the chain.
+///
+/// ```ignore
+/// let ext = Rc::new(RefCell::new(new_test_ext::<Runtime>(vec![(ALICE, 1_000_000_000_000)])));
+/// register_loopback::<Runtime, dyn ink::Trait>(&ext, ALICE);
+///
+/// assert_eq!(ink::TraitRef::new().double(21), 42);
+/// ```
+pub fn register_loopback<T, Definition>(ext: &Rc<RefCell<TestExternalities>>, origin: T::AccountId)
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+    Definition: ExtensionDescription + ?Sized,
+{
+    let proxy = ext.borrow_mut().execute_with(|| deploy_proxy::<T>(origin.clone()));
+    let func_ids = Definition::METHODS
+        .iter()
+        .map(|method| method.id)
+        .chain(Some(BATCH_FUNC_ID));
+
+    for func_id in func_ids {
+        register_chain_extension(Loopback::<T> {
+            ext: ext.clone(),
+            origin: origin.clone(),
+            proxy: proxy.clone(),
+            extension_id: Definition::ID,
+            func_id,
+        });
+    }
+}
+
+struct Loopback<T: pallet_contracts::Config> {
+    ext: Rc<RefCell<TestExternalities>>,
+    origin: T::AccountId,
+    proxy: T::AccountId,
+    extension_id: u16,
+    func_id: u16,
+}
+
+impl<T> ChainExtension for Loopback<T>
+where
+    T: pallet_contracts::Config,
+    T::AccountId: UncheckedFrom<T::Hash> + AsRef<[u8]>,
+{
+    fn func_id(&self) -> u32 {
+        ((self.extension_id as u32) << 16) | (self.func_id as u32)
+    }
+
+    fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+        let result = self.ext.borrow_mut().execute_with(|| {
+            call_extension::<T>(
+                self.origin.clone(),
+                self.proxy.clone(),
+                self.extension_id,
+                self.func_id,
+                input.to_vec(),
+            )
+        });
+
+        match result {
+            Ok((status, data)) => {
+                output.extend(data);
+                status
+            }
+            Err(error) => {
+                panic!(
+                    "the method {} of the chain extension {} failed: {:?}",
+                    self.func_id, self.extension_id, error
+                )
+            }
+        }
+    }
+}
@@ -0,0 +1,66 @@
+use obce::substrate::{
+    pallet_contracts::chain_extension::Ext,
+    sp_runtime::DispatchError,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    frame_support::assert_ok,
+    with_proxy,
+};
+use runtime::{
+    KillSwitch,
+    Origin,
+    Runtime,
+};
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    // The method was called `first` with the id `0x1` before.
+    #[obce(id = 0x2, aliases(0x1))]
+    fn renamed(&mut self, value: u32) -> u32;
+    #[obce(id = 0x3)]
+    fn other(&mut self, value: u32) -> u32;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation(kill_switch)]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn renamed(&mut self, value: u32) -> u32 {
+        value + 1
+    }
+
+    fn other(&mut self, value: u32) -> u32 {
+        value
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension, kill_switch);
+}
+
+#[test]
+fn old_ids_reach_the_renamed_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x2, 13u32), Ok(14));
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(14));
+    });
+}
+
+#[test]
+fn disabled_method_is_rejected_by_its_aliases() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_ok!(KillSwitch::disable_method(Origin::root(), 0x13, 0x2));
+
+        assert_eq!(
+            proxy.call_method::<u32>(0x13, 0x1, 13u32),
+            Err(DispatchError::Other("DisabledMethod"))
+        );
+        // Other methods of the chain extension are not affected.
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x3, 13u32), Ok(13));
+    });
+}
@@ -0,0 +1,75 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::chain_extension::Ext,
+    sp_core::H256,
+    sp_runtime::{
+        traits::{
+            BlakeTwo256,
+            Hash,
+        },
+        DispatchError,
+    },
+    AllowListProvider,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    wat_to_wasm,
+    with_proxy,
+    PROXY,
+};
+use runtime::Runtime;
+use std::cell::RefCell;
+
+thread_local! {
+    static ALLOWED: RefCell<Vec<H256>> = RefCell::new(Vec::new());
+}
+
+pub struct Allowed;
+
+impl AllowListProvider<Runtime> for Allowed {
+    fn is_allowed(_: &<Runtime as SysConfig>::AccountId, code_hash: &H256) -> bool {
+        ALLOWED.with(|allowed| allowed.borrow().contains(code_hash))
+    }
+}
+
+#[obce::definition(id = 0x13, allow = Allowed, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(&mut self, value: u32) -> u32;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, value: u32) -> u32 {
+        value
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn allowed_contract_calls_the_chain_extension() {
+    ALLOWED.with(|allowed| allowed.borrow_mut().push(BlakeTwo256::hash(&wat_to_wasm(PROXY))));
+
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+    });
+}
+
+#[test]
+fn not_allowed_contract_is_rejected() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call_method::<u32>(0x13, 0x1, 13u32),
+            Err(DispatchError::Other("CallerNotAllowed"))
+        );
+    });
+}
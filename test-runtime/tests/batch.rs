@@ -0,0 +1,127 @@
+use obce::{
+    codegen::BATCH_FUNC_ID,
+    substrate::{
+        pallet_contracts::{
+            self,
+            chain_extension::Ext,
+        },
+        sp_runtime::DispatchError,
+        ExtensionContext,
+    },
+};
+use obce_test_runtime::{
+    with_proxy,
+    Proxy,
+};
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Error {
+    Odd,
+}
+
+impl From<Error> for u32 {
+    fn from(_: Error) -> Self {
+        1
+    }
+}
+
+#[obce::definition(id = 0x13, error_code = Error, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1, handle_status = false)]
+    fn double(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2, handle_status = false)]
+    fn concat(&mut self, first: Vec<u8>, second: Vec<u8>) -> Vec<u8>;
+    #[obce(id = 0x3, handle_status = false)]
+    fn half(&mut self, value: u32) -> Result<u32, Error>;
+    #[obce(id = 0x4)]
+    fn checked_half(&mut self, value: u32) -> Result<u32, Error>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn double(&mut self, value: u32) -> u32 {
+        value * 2
+    }
+
+    fn concat(&mut self, mut first: Vec<u8>, second: Vec<u8>) -> Vec<u8> {
+        first.extend(second);
+        first
+    }
+
+    fn half(&mut self, value: u32) -> Result<u32, Error> {
+        if value % 2 == 0 {
+            Ok(value / 2)
+        } else {
+            Err(Error::Odd)
+        }
+    }
+
+    fn checked_half(&mut self, value: u32) -> Result<u32, Error> {
+        self.half(value)
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+fn batch(proxy: &Proxy<Runtime>, calls: Vec<(u16, Vec<u8>)>) -> Result<Vec<Vec<u8>>, DispatchError> {
+    proxy.call_method(0x13, BATCH_FUNC_ID, calls)
+}
+
+#[test]
+fn batch_executes_different_methods_in_order() {
+    with_proxy::<Runtime, _>(|proxy| {
+        let outputs = batch(
+            &proxy,
+            vec![
+                (0x1, 21u32.encode()),
+                (0x2, (vec![1u8], vec![2u8]).encode()),
+                (0x3, 4u32.encode()),
+                (0x3, 3u32.encode()),
+            ],
+        )
+        .unwrap();
+
+        assert_eq!(
+            outputs,
+            vec![
+                42u32.encode(),
+                vec![1u8, 2].encode(),
+                Ok::<u32, Error>(2).encode(),
+                Err::<u32, Error>(Error::Odd).encode(),
+            ]
+        );
+    });
+}
+
+#[test]
+fn batch_rejects_methods_with_status_code() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            batch(&proxy, vec![(0x1, 21u32.encode()), (0x4, 4u32.encode())]),
+            Err(DispatchError::Other("StatusCodeInBatch"))
+        );
+    });
+}
+
+#[test]
+fn batch_rejects_undecodable_input_of_call() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            batch(&proxy, vec![(0x1, 21u32.encode()), (0x1, vec![1])]),
+            Err(pallet_contracts::Error::<Runtime>::DecodingFailed.into())
+        );
+    });
+}
@@ -0,0 +1,77 @@
+use obce::substrate::{
+    pallet_contracts::{
+        self,
+        chain_extension::Ext,
+    },
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    new_test_ext,
+    wat_to_wasm,
+    with_proxy,
+    ALICE,
+    ENDOWMENT,
+    PROXY,
+};
+use runtime::Runtime;
+use std::cell::Cell;
+
+thread_local! {
+    static ENABLED: Cell<bool> = Cell::new(true);
+}
+
+fn enabled() -> bool {
+    ENABLED.with(Cell::get)
+}
+
+fn set_enabled(enabled: bool) {
+    ENABLED.with(|cell| cell.set(enabled));
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(&mut self, value: u32) -> u32;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation(enabled = enabled)]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, value: u32) -> u32 {
+        value
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn disabled_extension_rejects_calls() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+
+        set_enabled(false);
+        assert_eq!(
+            proxy.call_method::<u32>(0x13, 0x1, 13u32),
+            Err(pallet_contracts::Error::<Runtime>::NoChainExtension.into())
+        );
+    });
+}
+
+#[test]
+fn disabled_extension_rejects_contracts_that_use_it() {
+    set_enabled(false);
+
+    new_test_ext::<Runtime>(vec![(ALICE, ENDOWMENT)]).execute_with(|| {
+        assert_eq!(
+            pallet_contracts::Pallet::<Runtime>::bare_upload_code(ALICE, wat_to_wasm(PROXY), None).map(|_| ()),
+            Err(pallet_contracts::Error::<Runtime>::CodeRejected.into())
+        );
+    });
+}
@@ -0,0 +1,178 @@
+use obce::{
+    codegen::InvalidArgument,
+    substrate::{
+        frame_system::Config as SysConfig,
+        pallet_contracts::{
+            self,
+            chain_extension::{
+                BufInBufOutState,
+                Environment,
+                Ext,
+                UncheckedFrom,
+            },
+        },
+        sp_runtime::DispatchError,
+        CallOutcome,
+        ExtensionContext,
+        ExtensionHooks,
+    },
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+use std::cell::RefCell;
+
+#[derive(Debug, PartialEq, Eq)]
+enum Entry {
+    Before(u16),
+    Call(u16),
+    After(u16, CallOutcome),
+}
+
+thread_local! {
+    static LOG: RefCell<Vec<Entry>> = RefCell::new(Vec::new());
+}
+
+fn log(entry: Entry) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+fn take_log() -> Vec<Entry> {
+    LOG.with(|log| log.take())
+}
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub struct Error;
+
+impl From<InvalidArgument> for Error {
+    fn from(_: InvalidArgument) -> Self {
+        Error
+    }
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2)]
+    fn second(&mut self) -> Result<(), Error>;
+    #[obce(id = 0x3)]
+    fn rejected(&mut self) -> u32;
+    #[obce(id = 0x4)]
+    fn validated(&mut self, #[obce(range(1..=100))] value: u32) -> Result<u32, Error>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+impl<T: SysConfig> ExtensionHooks<T> for Extension {
+    fn before_call<E>(&mut self, func_id: u16, _: &mut Environment<E, BufInBufOutState>) -> Result<(), DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+    {
+        log(Entry::Before(func_id));
+        if func_id == 0x3 {
+            Err(DispatchError::Other("Rejected"))
+        } else {
+            Ok(())
+        }
+    }
+
+    fn after_call(&mut self, func_id: u16, outcome: &CallOutcome) {
+        log(Entry::After(func_id, *outcome));
+    }
+}
+
+#[obce::implementation(hooks)]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, value: u32) -> u32 {
+        log(Entry::Call(0x1));
+        value
+    }
+
+    fn second(&mut self) -> Result<(), Error> {
+        log(Entry::Call(0x2));
+        Err(Error)
+    }
+
+    fn rejected(&mut self) -> u32 {
+        log(Entry::Call(0x3));
+        0
+    }
+
+    fn validated(&mut self, value: u32) -> Result<u32, Error> {
+        log(Entry::Call(0x4));
+        Ok(value)
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn hooks_are_called_around_the_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+        assert_eq!(
+            take_log(),
+            vec![Entry::Before(0x1), Entry::Call(0x1), Entry::After(0x1, CallOutcome::Ok)]
+        );
+
+        assert_eq!(proxy.call_method::<Result<(), Error>>(0x13, 0x2, ()), Ok(Err(Error)));
+        assert_eq!(
+            take_log(),
+            vec![
+                Entry::Before(0x2),
+                Entry::Call(0x2),
+                Entry::After(0x2, CallOutcome::Error)
+            ]
+        );
+    });
+}
+
+#[test]
+fn after_call_observes_critical_errors() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call(0x13, 0x1, vec![1]),
+            Err(pallet_contracts::Error::<Runtime>::DecodingFailed.into())
+        );
+        assert_eq!(
+            take_log(),
+            vec![Entry::Before(0x1), Entry::After(0x1, CallOutcome::CriticalError)]
+        );
+    });
+}
+
+#[test]
+fn after_call_observes_invalid_arguments_as_errors() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<Result<u32, Error>>(0x13, 0x4, 13u32), Ok(Ok(13)));
+        assert_eq!(
+            take_log(),
+            vec![Entry::Before(0x4), Entry::Call(0x4), Entry::After(0x4, CallOutcome::Ok)]
+        );
+
+        assert_eq!(proxy.call_method::<Result<u32, Error>>(0x13, 0x4, 0u32), Ok(Err(Error)));
+        assert_eq!(
+            take_log(),
+            vec![Entry::Before(0x4), Entry::After(0x4, CallOutcome::Error)]
+        );
+    });
+}
+
+#[test]
+fn error_of_before_call_stops_the_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call(0x13, 0x3, Vec::new()), Err(DispatchError::Other("Rejected")));
+        assert_eq!(take_log(), vec![Entry::Before(0x3)]);
+    });
+}
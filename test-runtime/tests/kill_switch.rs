@@ -0,0 +1,112 @@
+use obce::substrate::{
+    pallet::Event,
+    pallet_contracts::chain_extension::Ext,
+    sp_runtime::DispatchError,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    frame_support::{
+        assert_noop,
+        assert_ok,
+    },
+    new_test_ext,
+    with_proxy,
+    ALICE,
+    ENDOWMENT,
+};
+use runtime::{
+    KillSwitch,
+    Origin,
+    Runtime,
+    System,
+};
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2)]
+    fn second(&mut self, value: u32) -> u32;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation(kill_switch)]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, value: u32) -> u32 {
+        value
+    }
+
+    fn second(&mut self, value: u32) -> u32 {
+        value
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension, kill_switch);
+}
+
+#[test]
+fn only_root_disables_and_enables_methods() {
+    new_test_ext::<Runtime>(vec![(ALICE, ENDOWMENT)]).execute_with(|| {
+        assert_noop!(
+            KillSwitch::disable_method(Origin::signed(ALICE), 0x13, 0x1),
+            DispatchError::BadOrigin
+        );
+        assert_ok!(KillSwitch::disable_method(Origin::root(), 0x13, 0x1));
+        assert_noop!(
+            KillSwitch::enable_method(Origin::signed(ALICE), 0x13, 0x1),
+            DispatchError::BadOrigin
+        );
+        assert!(KillSwitch::is_disabled(0x13, 0x1));
+    });
+}
+
+#[test]
+fn disabled_method_is_rejected() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+
+        assert_ok!(KillSwitch::disable_method(Origin::root(), 0x13, 0x1));
+        System::assert_last_event(
+            Event::<Runtime>::MethodDisabled {
+                extension_id: 0x13,
+                func_id: 0x1,
+            }
+            .into(),
+        );
+
+        assert_eq!(
+            proxy.call_method::<u32>(0x13, 0x1, 13u32),
+            Err(DispatchError::Other("DisabledMethod"))
+        );
+        // Other methods of the chain extension are not affected.
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x2, 13u32), Ok(13));
+    });
+}
+
+#[test]
+fn enabled_method_is_callable_again() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_ok!(KillSwitch::disable_method(Origin::root(), 0x13, 0x1));
+        assert_eq!(
+            proxy.call_method::<u32>(0x13, 0x1, 13u32),
+            Err(DispatchError::Other("DisabledMethod"))
+        );
+
+        assert_ok!(KillSwitch::enable_method(Origin::root(), 0x13, 0x1));
+        System::assert_last_event(
+            Event::<Runtime>::MethodEnabled {
+                extension_id: 0x13,
+                func_id: 0x1,
+            }
+            .into(),
+        );
+        assert!(!KillSwitch::is_disabled(0x13, 0x1));
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+    });
+}
@@ -0,0 +1,139 @@
+use obce::{
+    codegen::MethodMetadata,
+    substrate::{
+        frame_system::Config as SysConfig,
+        pallet_contracts::chain_extension::{
+            Ext,
+            UncheckedFrom,
+        },
+        sp_runtime::DispatchError,
+        ExtensionContext,
+        Layer,
+    },
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+use std::cell::RefCell;
+
+thread_local! {
+    static LOG: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+fn log(entry: String) {
+    LOG.with(|log| log.borrow_mut().push(entry));
+}
+
+fn take_log() -> Vec<String> {
+    LOG.with(|log| log.take())
+}
+
+/// Logs the method before and after the next layer.
+pub struct Logger;
+
+impl<T: SysConfig> Layer<T> for Logger {
+    fn call<'a, 'b, E, Extension, F>(
+        context: &mut ExtensionContext<'a, 'b, E, T, Extension>,
+        extension_id: u16,
+        method: MethodMetadata,
+        next: F,
+    ) -> Result<(), DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+        F: FnOnce(&mut ExtensionContext<'a, 'b, E, T, Extension>) -> Result<(), DispatchError>,
+    {
+        log(format!("logger before {}::{}", extension_id, method.name));
+        let result = next(context);
+        log(format!("logger after {}::{} {:?}", extension_id, method.name, result));
+        result
+    }
+}
+
+/// Rejects methods that are not views.
+pub struct ViewOnly;
+
+impl<T: SysConfig> Layer<T> for ViewOnly {
+    fn call<'a, 'b, E, Extension, F>(
+        context: &mut ExtensionContext<'a, 'b, E, T, Extension>,
+        _: u16,
+        method: MethodMetadata,
+        next: F,
+    ) -> Result<(), DispatchError>
+    where
+        E: Ext<T = T>,
+        <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+        F: FnOnce(&mut ExtensionContext<'a, 'b, E, T, Extension>) -> Result<(), DispatchError>,
+    {
+        log(format!("view_only {}", method.name));
+        if method.view {
+            next(context)
+        } else {
+            Err(DispatchError::Other("NotView"))
+        }
+    }
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1, view)]
+    fn first(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2)]
+    fn second(&mut self, value: u32) -> u32;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation(layers(Logger, ViewOnly))]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, value: u32) -> u32 {
+        log("first".into());
+        value
+    }
+
+    fn second(&mut self, value: u32) -> u32 {
+        log("second".into());
+        value
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn first_layer_is_the_outermost() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+        assert_eq!(
+            take_log(),
+            vec![
+                "logger before 19::first".to_string(),
+                "view_only first".to_string(),
+                "first".to_string(),
+                "logger after 19::first Ok(())".to_string(),
+            ]
+        );
+    });
+}
+
+#[test]
+fn layer_stops_the_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call_method::<u32>(0x13, 0x2, 13u32),
+            Err(DispatchError::Other("NotView"))
+        );
+        assert_eq!(
+            take_log(),
+            vec![
+                "logger before 19::second".to_string(),
+                "view_only second".to_string(),
+                r#"logger after 19::second Err(Other("NotView"))"#.to_string(),
+            ]
+        );
+    });
+}
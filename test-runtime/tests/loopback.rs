@@ -0,0 +1,95 @@
+#![cfg(feature = "ink")]
+
+use obce::substrate::{
+    pallet_contracts::chain_extension::Ext,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    new_test_ext,
+    register_loopback,
+    ALICE,
+    ENDOWMENT,
+};
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+use std::{
+    cell::RefCell,
+    rc::Rc,
+};
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Error {
+    Odd,
+}
+
+#[obce::definition(id = 0x13, both)]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn double(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2)]
+    fn half(&mut self, value: u32) -> Result<u32, Error>;
+    #[obce(id = 0x3)]
+    fn concat(&mut self, first: Vec<u8>, second: Vec<u8>) -> Vec<u8>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> substrate::Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn double(&mut self, value: u32) -> u32 {
+        value * 2
+    }
+
+    fn half(&mut self, value: u32) -> Result<u32, Error> {
+        if value % 2 == 0 {
+            Ok(value / 2)
+        } else {
+            Err(Error::Odd)
+        }
+    }
+
+    fn concat(&mut self, mut first: Vec<u8>, second: Vec<u8>) -> Vec<u8> {
+        first.extend(second);
+        first
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+fn register() {
+    let ext = Rc::new(RefCell::new(new_test_ext::<Runtime>(vec![(ALICE, ENDOWMENT)])));
+    register_loopback::<Runtime, dyn ink::Trait>(&ext, ALICE);
+}
+
+#[test]
+fn ink_side_calls_the_implementation() {
+    register();
+
+    let extension = ink::TraitRef::new();
+    assert_eq!(extension.double(21), 42);
+    assert_eq!(extension.half(4), Ok(2));
+    assert_eq!(extension.half(3), Err(Error::Odd));
+    assert_eq!(extension.concat(vec![1, 2], vec![3]), vec![1, 2, 3]);
+}
+
+#[test]
+fn ink_side_calls_the_batch() {
+    register();
+
+    let mut batch = ink::TraitBatch::new();
+    let double = batch.double(21);
+    let half = batch.half(3);
+    let output = batch.call();
+
+    assert_eq!(output.get(&double), Ok(42));
+    assert_eq!(output.get(&half), Ok(Err(Error::Odd)));
+}
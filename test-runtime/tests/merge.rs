@@ -0,0 +1,81 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::{
+        self,
+        chain_extension::{
+            Ext,
+            UncheckedFrom,
+        },
+    },
+    sp_runtime::DispatchError,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    pallet_timestamp,
+    with_proxy,
+};
+use runtime::Runtime;
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait First {
+    #[obce(id = 0x1)]
+    fn double(&mut self, value: u32) -> u32;
+}
+
+#[obce::definition(id = 0x14, substrate_cfg = all(), ink_cfg = any())]
+pub trait Second {
+    #[obce(id = 0x1)]
+    fn now(&mut self) -> u64;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation(merge(Second))]
+impl<'a, 'b, E, T> First for ExtensionContext<'a, 'b, E, T, Extension>
+where
+    T: SysConfig + pallet_contracts::Config,
+    E: Ext<T = T>,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    fn double(&mut self, value: u32) -> u32 {
+        value * 2
+    }
+}
+
+// The merged implementation has the bound that the main one doesn't have.
+#[obce::implementation(dispatch_only)]
+impl<'a, 'b, E, T> Second for ExtensionContext<'a, 'b, E, T, Extension>
+where
+    T: SysConfig + pallet_contracts::Config + pallet_timestamp::Config<Moment = u64>,
+    E: Ext<T = T>,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    fn now(&mut self) -> u64 {
+        pallet_timestamp::Pallet::<T>::get()
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn merged_extension_routes_calls_by_extension_id() {
+    with_proxy::<Runtime, _>(|proxy| {
+        pallet_timestamp::Pallet::<Runtime>::set_timestamp(42);
+
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 21u32), Ok(42));
+        assert_eq!(proxy.call_method::<u64>(0x14, 0x1, ()), Ok(42));
+    });
+}
+
+#[test]
+fn merged_extension_rejects_unknown_extension_id() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call_method::<u32>(0x15, 0x1, 21u32),
+            Err(DispatchError::Other("InvalidExtensionId"))
+        );
+    });
+}
@@ -0,0 +1,68 @@
+use obce::{
+    codegen::BATCH_FUNC_ID,
+    substrate::{
+        pallet_contracts::chain_extension::Ext,
+        ExtensionContext,
+    },
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+use scale::Encode;
+
+const WEIGHT_PER_BYTE: u64 = 1_000_000;
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn plain(&mut self, len: u32) -> Vec<u8>;
+    #[obce(id = 0x2, output_weight_per_byte = 1_000_000)]
+    fn weighted(&mut self, len: u32) -> Vec<u8>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn plain(&mut self, len: u32) -> Vec<u8> {
+        vec![0; len as usize]
+    }
+
+    fn weighted(&mut self, len: u32) -> Vec<u8> {
+        vec![0; len as usize]
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn output_is_charged_per_byte() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<Vec<u8>>(0x13, 0x2, 100u32), Ok(vec![0; 100]));
+
+        let plain = proxy.gas_consumed(0x13, 0x1, 100u32.encode());
+        let weighted = proxy.gas_consumed(0x13, 0x2, 100u32.encode());
+        let output_len = vec![0u8; 100].encode().len() as u64;
+        assert_eq!(weighted.ref_time() - plain.ref_time(), WEIGHT_PER_BYTE * output_len);
+    });
+}
+
+#[test]
+fn output_is_charged_per_byte_in_batch() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call_method::<Vec<Vec<u8>>>(0x13, BATCH_FUNC_ID, vec![(0x2u16, 100u32.encode())]),
+            Ok(vec![vec![0u8; 100].encode()])
+        );
+
+        let plain = proxy.gas_consumed(0x13, BATCH_FUNC_ID, vec![(0x1u16, 100u32.encode())].encode());
+        let weighted = proxy.gas_consumed(0x13, BATCH_FUNC_ID, vec![(0x2u16, 100u32.encode())].encode());
+        let output_len = vec![0u8; 100].encode().len() as u64;
+        assert_eq!(weighted.ref_time() - plain.ref_time(), WEIGHT_PER_BYTE * output_len);
+    });
+}
@@ -0,0 +1,122 @@
+use obce::substrate::{
+    frame_support::weights::Weight,
+    frame_system::Config as SysConfig,
+    pallet_contracts::chain_extension::{
+        BufInBufOutState,
+        Environment,
+        Ext,
+        UncheckedFrom,
+    },
+    sp_runtime::DispatchError,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    with_proxy,
+    Proxy,
+};
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Error {
+    Reentrant,
+    Failed,
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(&mut self, func_id: u16, depth: u32) -> Result<u32, Error>;
+    #[obce(id = 0x2)]
+    fn second(&mut self, func_id: u16, depth: u32) -> Result<u32, Error>;
+    #[obce(id = 0x3)]
+    fn unguarded(&mut self, func_id: u16, depth: u32) -> Result<u32, Error>;
+}
+
+/// Calls the method `func_id` via the calling contract again until the `depth` is zero.
+fn reenter<E>(env: &mut Environment<E, BufInBufOutState>, func_id: u16, depth: u32) -> Result<u32, Error>
+where
+    E: Ext,
+    <E::T as SysConfig>::AccountId: UncheckedFrom<<E::T as SysConfig>::Hash> + AsRef<[u8]>,
+{
+    if depth == 0 {
+        return Ok(0)
+    }
+
+    let ext = env.ext();
+    let address = ext.address().clone();
+    let mut input = ((0x13u32 << 16) | func_id as u32).to_le_bytes().to_vec();
+    input.extend((func_id, depth - 1).encode());
+
+    match ext.call(Weight::zero(), address, Default::default(), input, true) {
+        // The output of the proxy starts with the status code.
+        Ok(output) => {
+            Result::<u32, Error>::decode(&mut &output.data[4..])
+                .expect("the output is `Result<u32, Error>`")
+                .map(|depth| depth + 1)
+        }
+        Err(error) if error.error == DispatchError::Other("ReentrantCall") => Err(Error::Reentrant),
+        Err(_) => Err(Error::Failed),
+    }
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    #[obce(non_reentrant)]
+    fn first(&mut self, func_id: u16, depth: u32) -> Result<u32, Error> {
+        reenter(&mut self.env, func_id, depth)
+    }
+
+    #[obce(non_reentrant)]
+    fn second(&mut self, func_id: u16, depth: u32) -> Result<u32, Error> {
+        reenter(&mut self.env, func_id, depth)
+    }
+
+    fn unguarded(&mut self, func_id: u16, depth: u32) -> Result<u32, Error> {
+        reenter(&mut self.env, func_id, depth)
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+fn call(proxy: &Proxy<Runtime>, func_id: u16, next_func_id: u16, depth: u32) -> Result<u32, Error> {
+    proxy.call_method(0x13, func_id, (next_func_id, depth)).unwrap()
+}
+
+#[test]
+fn guarded_method_rejects_reentrant_call() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call(&proxy, 0x1, 0x1, 0), Ok(0));
+        assert_eq!(call(&proxy, 0x1, 0x1, 1), Err(Error::Reentrant));
+        // The guard is released after the call.
+        assert_eq!(call(&proxy, 0x1, 0x1, 0), Ok(0));
+    });
+}
+
+#[test]
+fn guarded_method_rejects_call_of_another_guarded_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call(&proxy, 0x1, 0x2, 1), Err(Error::Reentrant));
+        assert_eq!(call(&proxy, 0x2, 0x1, 1), Err(Error::Reentrant));
+    });
+}
+
+#[test]
+fn unguarded_method_allows_reentrant_call() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call(&proxy, 0x3, 0x3, 2), Ok(2));
+        assert_eq!(call(&proxy, 0x3, 0x1, 1), Ok(1));
+        assert_eq!(call(&proxy, 0x1, 0x3, 1), Ok(1));
+    });
+}
@@ -0,0 +1,71 @@
+use obce::substrate::{
+    pallet_contracts::{
+        self,
+        chain_extension::Ext,
+    },
+    sp_runtime::DispatchError,
+    ExtensionContext,
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn double(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2)]
+    fn concat(&mut self, first: Vec<u8>, second: Vec<u8>) -> Vec<u8>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn double(&mut self, value: u32) -> u32 {
+        value * 2
+    }
+
+    fn concat(&mut self, mut first: Vec<u8>, second: Vec<u8>) -> Vec<u8> {
+        first.extend(second);
+        first
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn contract_calls_methods_of_the_chain_extension() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 21u32), Ok(42));
+        assert_eq!(
+            proxy.call_method::<Vec<u8>>(0x13, 0x2, (vec![1u8, 2], vec![3u8])),
+            Ok(vec![1, 2, 3])
+        );
+    });
+}
+
+#[test]
+fn unknown_method_is_critical_error() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call(0x13, 0x3, Vec::new()),
+            Err(DispatchError::Other("InvalidFunctionId"))
+        );
+    });
+}
+
+#[test]
+fn undecodable_input_is_critical_error() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call(0x13, 0x1, vec![1]),
+            Err(pallet_contracts::Error::<Runtime>::DecodingFailed.into())
+        );
+    });
+}
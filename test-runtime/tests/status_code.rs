@@ -0,0 +1,108 @@
+use obce::substrate::{
+    pallet_contracts::chain_extension::Ext,
+    ExtensionContext,
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub struct RandomReadErr;
+
+impl From<RandomReadErr> for u32 {
+    fn from(_: RandomReadErr) -> Self {
+        2
+    }
+}
+
+#[obce::from_ink(substrate_cfg = all(), ink_cfg = any())]
+pub trait RandExtension {
+    type ErrorCode = RandomReadErr;
+
+    #[ink(extension = 1101, returns_result = false)]
+    fn fetch_random(subject: [u8; 32]) -> [u8; 32];
+
+    #[ink(extension = 1102, handle_status = false)]
+    fn checked_random(subject: [u8; 32]) -> Result<[u8; 32], RandomReadErr>;
+
+    #[ink(extension = 1103, handle_status = false)]
+    fn seed() -> u32;
+
+    #[ink(extension = 1104)]
+    fn read_random(subject: [u8; 32]) -> Result<[u8; 32], RandomReadErr>;
+}
+
+fn random(subject: [u8; 32]) -> Result<[u8; 32], RandomReadErr> {
+    if subject == [0; 32] {
+        Err(RandomReadErr)
+    } else {
+        Ok(subject)
+    }
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> RandExtension for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn fetch_random(&mut self, subject: [u8; 32]) -> Result<[u8; 32], RandomReadErr> {
+        random(subject)
+    }
+
+    fn checked_random(&mut self, subject: [u8; 32]) -> Result<[u8; 32], RandomReadErr> {
+        random(subject)
+    }
+
+    fn seed(&mut self) -> u32 {
+        13
+    }
+
+    fn read_random(&mut self, subject: [u8; 32]) -> Result<[u8; 32], RandomReadErr> {
+        random(subject)
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+// Ids of ink! chain extensions don't have the extension id, so it is zero in calls.
+#[test]
+fn error_is_returned_as_status_code() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call(0, 1101, [1; 32].encode()), Ok((0, [1u8; 32].encode())));
+        assert_eq!(proxy.call(0, 1101, [0; 32].encode()), Ok((2, Vec::new())));
+    });
+}
+
+#[test]
+fn error_is_encoded_without_status_code() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call(0, 1102, [1; 32].encode()),
+            Ok((0, Ok::<_, RandomReadErr>([1u8; 32]).encode()))
+        );
+        assert_eq!(
+            proxy.call(0, 1102, [0; 32].encode()),
+            Ok((0, Err::<[u8; 32], _>(RandomReadErr).encode()))
+        );
+        assert_eq!(proxy.call(0, 1103, Vec::new()), Ok((0, 13u32.encode())));
+    });
+}
+
+#[test]
+fn error_is_returned_as_status_code_and_result_is_encoded() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call(0, 1104, [1; 32].encode()),
+            Ok((0, Ok::<_, RandomReadErr>([1u8; 32]).encode()))
+        );
+        assert_eq!(proxy.call(0, 1104, [0; 32].encode()), Ok((2, Vec::new())));
+    });
+}
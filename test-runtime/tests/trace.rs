@@ -0,0 +1,167 @@
+use obce::substrate::{
+    log::{
+        self,
+        LevelFilter,
+        Log,
+        Metadata,
+        Record,
+    },
+    pallet_contracts::{
+        self,
+        chain_extension::Ext,
+    },
+    sp_runtime::DispatchError,
+    CriticalError,
+    ExtensionContext,
+    SupportCriticalError,
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+use std::{
+    cell::RefCell,
+    sync::Once,
+};
+
+const TARGET: &str = "obce::trace_test";
+
+thread_local! {
+    static MESSAGES: RefCell<Vec<String>> = RefCell::new(Vec::new());
+}
+
+/// Collects messages of the `TARGET` logged by the current thread.
+struct Capture;
+
+impl Log for Capture {
+    fn enabled(&self, metadata: &Metadata) -> bool {
+        metadata.target() == TARGET
+    }
+
+    fn log(&self, record: &Record) {
+        if self.enabled(record.metadata()) {
+            MESSAGES.with(|messages| messages.borrow_mut().push(record.args().to_string()));
+        }
+    }
+
+    fn flush(&self) {}
+}
+
+fn capture_messages() {
+    static INIT: Once = Once::new();
+    INIT.call_once(|| {
+        log::set_logger(&Capture).unwrap();
+        log::set_max_level(LevelFilter::Debug);
+    });
+}
+
+fn take_messages() -> Vec<String> {
+    MESSAGES.with(|messages| messages.take())
+}
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Error {
+    NonCritical,
+    Critical(CriticalError),
+}
+
+impl SupportCriticalError for Error {
+    fn try_to_critical(self) -> Result<CriticalError, Self> {
+        match self {
+            Error::Critical(error) => Ok(error),
+            _ => Err(self),
+        }
+    }
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(&mut self, value: u32) -> u32;
+    #[obce(id = 0x2)]
+    fn second(&mut self) -> Result<(), Error>;
+    #[obce(id = 0x3)]
+    fn third(&mut self) -> Result<(), Error>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation(trace = "obce::trace_test")]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, value: u32) -> u32 {
+        value
+    }
+
+    fn second(&mut self) -> Result<(), Error> {
+        Err(Error::NonCritical)
+    }
+
+    fn third(&mut self) -> Result<(), Error> {
+        Err(Error::Critical(DispatchError::Other("Critical")))
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn successful_call_is_traced() {
+    capture_messages();
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call_method::<u32>(0x13, 0x1, 13u32), Ok(13));
+        let messages = take_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("first (func_id: 1, input_len: 4, weight: "));
+        assert!(messages[0].ends_with("): Ok"));
+    });
+}
+
+#[test]
+fn non_critical_error_is_traced() {
+    capture_messages();
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call_method::<Result<(), Error>>(0x13, 0x2, ()),
+            Ok(Err(Error::NonCritical))
+        );
+        let messages = take_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("second (func_id: 2, input_len: 0, weight: "));
+        assert!(messages[0].ends_with("): Error"));
+    });
+}
+
+#[test]
+fn critical_error_is_traced_with_the_error() {
+    capture_messages();
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(proxy.call(0x13, 0x3, Vec::new()), Err(DispatchError::Other("Critical")));
+        let messages = take_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("third (func_id: 3, input_len: 0, weight: "));
+        assert!(messages[0].ends_with("): CriticalError Other(\"Critical\")"));
+    });
+}
+
+#[test]
+fn decoding_failure_is_traced_as_critical_error() {
+    capture_messages();
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call(0x13, 0x1, vec![1]),
+            Err(pallet_contracts::Error::<Runtime>::DecodingFailed.into())
+        );
+        let messages = take_messages();
+        assert_eq!(messages.len(), 1);
+        assert!(messages[0].starts_with("first (func_id: 1, input_len: 1, weight: "));
+        assert!(messages[0].contains("): CriticalError "));
+        assert!(messages[0].contains("DecodingFailed"));
+    });
+}
@@ -0,0 +1,108 @@
+use obce::substrate::{
+    frame_support::storage::unhashed,
+    pallet_contracts::chain_extension::Ext,
+    ExtensionContext,
+};
+use obce_test_runtime::{
+    with_proxy,
+    Proxy,
+};
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+
+const KEY: &[u8] = b"obce::value";
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Error {
+    Odd,
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn set(&mut self, value: u32) -> Result<(), Error>;
+    #[obce(id = 0x2)]
+    fn set_unchecked(&mut self, value: u32) -> Result<(), Error>;
+    #[obce(id = 0x3, view)]
+    fn set_view(&mut self, value: u32) -> u32;
+}
+
+fn set(value: u32) -> Result<(), Error> {
+    unhashed::put(KEY, &value);
+    if value % 2 == 0 {
+        Ok(())
+    } else {
+        Err(Error::Odd)
+    }
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    #[obce(transactional)]
+    fn set(&mut self, value: u32) -> Result<(), Error> {
+        set(value)
+    }
+
+    fn set_unchecked(&mut self, value: u32) -> Result<(), Error> {
+        set(value)
+    }
+
+    fn set_view(&mut self, value: u32) -> u32 {
+        unhashed::put(KEY, &value);
+        value
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+fn call<O: Decode>(proxy: &Proxy<Runtime>, func_id: u16, value: u32) -> O {
+    proxy.call_method(0x13, func_id, value).unwrap()
+}
+
+fn value() -> Option<u32> {
+    unhashed::get(KEY)
+}
+
+#[test]
+fn transactional_method_commits_ok() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call::<Result<(), Error>>(&proxy, 0x1, 2), Ok(()));
+        assert_eq!(value(), Some(2));
+    });
+}
+
+#[test]
+fn transactional_method_rolls_back_error() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call::<Result<(), Error>>(&proxy, 0x1, 2), Ok(()));
+        assert_eq!(call::<Result<(), Error>>(&proxy, 0x1, 3), Err(Error::Odd));
+        assert_eq!(value(), Some(2));
+    });
+}
+
+#[test]
+fn not_transactional_method_keeps_changes_on_error() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call::<Result<(), Error>>(&proxy, 0x2, 3), Err(Error::Odd));
+        assert_eq!(value(), Some(3));
+    });
+}
+
+#[test]
+fn view_method_rolls_back_changes() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call::<u32>(&proxy, 0x3, 2), 2);
+        assert_eq!(value(), None);
+    });
+}
@@ -0,0 +1,63 @@
+use obce::substrate::{
+    frame_system::Config as SysConfig,
+    pallet_contracts::chain_extension::Ext,
+    sp_core::crypto::AccountId32,
+    ExtensionContext,
+};
+use obce_test_runtime::with_proxy;
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+use scale_info::TypeInfo;
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode, TypeInfo)]
+pub struct Account(pub [u8; 32]);
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1, substrate_type = <Runtime as SysConfig>::AccountId)]
+    fn echo(&mut self, #[obce(substrate_type = <Runtime as SysConfig>::AccountId)] who: Account) -> Account;
+    #[obce(id = 0x2, substrate_type = u64)]
+    fn mismatched(&mut self) -> u128;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn echo(&mut self, who: AccountId32) -> AccountId32 {
+        who
+    }
+
+    fn mismatched(&mut self) -> u64 {
+        0
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+#[test]
+fn runtime_types_with_the_same_shape_are_accepted() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(
+            proxy.call_method::<Account>(0x13, 0x1, Account([2; 32])),
+            Ok(Account([2; 32]))
+        );
+    });
+}
+
+#[test]
+#[should_panic(expected = "encode differently")]
+fn runtime_types_with_another_shape_are_rejected() {
+    with_proxy::<Runtime, _>(|proxy| {
+        let _ = proxy.call(0x13, 0x2, Vec::new());
+    });
+}
@@ -0,0 +1,93 @@
+use obce::{
+    codegen::InvalidArgument,
+    substrate::{
+        pallet_contracts::chain_extension::Ext,
+        ExtensionContext,
+    },
+};
+use obce_test_runtime::{
+    with_proxy,
+    Proxy,
+};
+use runtime::Runtime;
+use scale::{
+    Decode,
+    Encode,
+};
+use std::cell::Cell;
+
+thread_local! {
+    static CALLS: Cell<u32> = Cell::new(0);
+}
+
+#[derive(Debug, PartialEq, Eq, Encode, Decode)]
+pub enum Error {
+    InvalidArgument,
+}
+
+impl From<InvalidArgument> for Error {
+    fn from(_: InvalidArgument) -> Self {
+        Error::InvalidArgument
+    }
+}
+
+fn is_not_reserved(value: &u32) -> bool {
+    *value != 13
+}
+
+#[obce::definition(id = 0x13, substrate_cfg = all(), ink_cfg = any())]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    fn first(
+        &mut self,
+        #[obce(range(1..=100), validate = is_not_reserved)] amount: u32,
+        #[obce(max_len = 4)] name: Vec<u8>,
+    ) -> Result<u32, Error>;
+}
+
+#[derive(Default)]
+pub struct Extension;
+
+#[obce::implementation]
+impl<'a, 'b, E> Trait for ExtensionContext<'a, 'b, E, Runtime, Extension>
+where
+    E: Ext<T = Runtime>,
+{
+    fn first(&mut self, amount: u32, _: Vec<u8>) -> Result<u32, Error> {
+        CALLS.with(|calls| calls.set(calls.get() + 1));
+        Ok(amount)
+    }
+}
+
+mod runtime {
+    obce_test_runtime::construct_test_runtime!(Runtime, crate::Extension);
+}
+
+fn call(proxy: &Proxy<Runtime>, amount: u32, name: Vec<u8>) -> Result<u32, Error> {
+    proxy.call_method(0x13, 0x1, (amount, name)).unwrap()
+}
+
+fn calls() -> u32 {
+    CALLS.with(Cell::get)
+}
+
+#[test]
+fn valid_arguments_are_passed_to_the_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        assert_eq!(call(&proxy, 10, vec![1, 2]), Ok(10));
+        assert_eq!(calls(), 1);
+    });
+}
+
+#[test]
+fn invalid_arguments_are_rejected_before_the_method() {
+    with_proxy::<Runtime, _>(|proxy| {
+        // Out of the range.
+        assert_eq!(call(&proxy, 0, vec![1, 2]), Err(Error::InvalidArgument));
+        // Rejected by the `validate` function.
+        assert_eq!(call(&proxy, 13, vec![1, 2]), Err(Error::InvalidArgument));
+        // Longer than `max_len`.
+        assert_eq!(call(&proxy, 10, vec![1, 2, 3, 4, 5]), Err(Error::InvalidArgument));
+        assert_eq!(calls(), 0);
+    });
+}
@@ -0,0 +1,135 @@
+#![cfg(feature = "ink")]
+
+use core::{
+    any::TypeId,
+    mem::size_of,
+};
+use obce::{
+    codegen::MethodDescription,
+    ink::ink_env::{
+        DefaultEnvironment,
+        Environment,
+    },
+};
+
+#[obce::definition(id = 0x13)]
+pub trait Trait {
+    fn first(&mut self, value: u32) -> u32;
+}
+
+obce::ink_environment!(pub CustomEnvironment: Trait);
+
+fn same<A: 'static, B: 'static>() -> bool {
+    TypeId::of::<A>() == TypeId::of::<B>()
+}
+
+#[test]
+fn ink_environment_uses_default_types() {
+    assert!(same::<
+        <CustomEnvironment as Environment>::AccountId,
+        <DefaultEnvironment as Environment>::AccountId,
+    >());
+    assert!(same::<
+        <CustomEnvironment as Environment>::Balance,
+        <DefaultEnvironment as Environment>::Balance,
+    >());
+    assert_eq!(
+        <CustomEnvironment as Environment>::MAX_EVENT_TOPICS,
+        <DefaultEnvironment as Environment>::MAX_EVENT_TOPICS
+    );
+}
+
+#[test]
+fn ink_environment_implements_definitions() {
+    fn implements<T: Trait>() {}
+
+    implements::<<CustomEnvironment as Environment>::ChainExtension>();
+    assert!(same::<
+        <CustomEnvironment as Environment>::ChainExtension,
+        obce::ink::Extensions<CustomEnvironment>,
+    >());
+}
+
+#[test]
+fn ink_environment_has_type_info() {
+    let _ = scale_info::meta_type::<CustomEnvironment>();
+}
+
+#[test]
+fn caller_type_is_zero_sized() {
+    assert_eq!(size_of::<TraitRef>(), 0);
+    assert_eq!(TraitDynRef::new().extension_id(), 0x13);
+    assert_eq!(TraitRef::with_extension_id(0x14).extension_id(), 0x14);
+}
+
+#[cfg(feature = "ink-std")]
+mod off_chain {
+    use super::*;
+    use obce::ink::ink_env::test::{
+        register_chain_extension,
+        ChainExtension,
+    };
+
+    const FIRST: u16 = <dyn Trait as MethodDescription<1877126689>>::ID;
+
+    /// Returns the input as the output.
+    struct Echo {
+        func_id: u32,
+    }
+
+    impl ChainExtension for Echo {
+        fn func_id(&self) -> u32 {
+            self.func_id
+        }
+
+        fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+            output.extend_from_slice(input);
+            0
+        }
+    }
+
+    /// Returns an output that can't be decoded.
+    struct Truncate {
+        func_id: u32,
+    }
+
+    impl ChainExtension for Truncate {
+        fn func_id(&self) -> u32 {
+            self.func_id
+        }
+
+        fn call(&mut self, input: &[u8], output: &mut Vec<u8>) -> u32 {
+            output.extend_from_slice(&input[..1]);
+            0
+        }
+    }
+
+    #[test]
+    fn try_methods_return_decode_errors() {
+        register_chain_extension(Echo {
+            func_id: (0x13 << 16) | FIRST as u32,
+        });
+        register_chain_extension(Truncate {
+            func_id: (0x14 << 16) | FIRST as u32,
+        });
+
+        assert_eq!(TraitRef::new().try_first(13), Ok(13));
+        assert!(matches!(
+            TraitRef::with_extension_id(0x14).try_first(14),
+            Err(obce::ink::ink_env::Error::Decode(_))
+        ));
+    }
+
+    #[test]
+    fn caller_type_calls_the_chain_extension() {
+        register_chain_extension(Echo {
+            func_id: (0x13 << 16) | FIRST as u32,
+        });
+        register_chain_extension(Echo {
+            func_id: (0x14 << 16) | FIRST as u32,
+        });
+
+        assert_eq!(TraitRef::new().first(13), 13);
+        assert_eq!(TraitRef::with_extension_id(0x14).first(14), 14);
+    }
+}
@@ -1,4 +1,8 @@
-use obce::codegen::ExtensionDescription;
+use obce::codegen::{
+    ExtensionDescription,
+    MethodDescription,
+    MethodMetadata,
+};
 
 #[test]
 fn definition_extension_id_by_name() {
@@ -15,3 +19,221 @@ fn definition_extension_id_by_number() {
 
     assert_eq!(<dyn Trait as ExtensionDescription>::ID, 0x13);
 }
+
+#[test]
+fn definition_method_id_by_number() {
+    #[obce::definition]
+    #[allow(dead_code)]
+    pub trait Trait {
+        #[obce(id = 0x13)]
+        fn first(&mut self) -> u32;
+    }
+
+    assert_eq!(<dyn Trait as MethodDescription<1877126689>>::ID, 0x13);
+}
+
+#[test]
+fn definition_output_weight_per_byte() {
+    #[obce::definition(output_weight_per_byte = 10)]
+    #[allow(dead_code)]
+    pub trait Trait {
+        fn first(&mut self) -> u32;
+        #[obce(output_weight_per_byte = 20)]
+        fn second(&mut self) -> u32;
+    }
+
+    assert_eq!(
+        <dyn Trait as MethodDescription<1877126689>>::OUTPUT_WEIGHT_PER_BYTE,
+        Some(10)
+    );
+    assert_eq!(
+        <dyn Trait as MethodDescription<1041003283>>::OUTPUT_WEIGHT_PER_BYTE,
+        Some(20)
+    );
+}
+
+#[test]
+fn definition_view_methods() {
+    #[obce::definition]
+    #[allow(dead_code)]
+    pub trait Trait {
+        #[obce(id = 0x1, view)]
+        fn first(&mut self) -> u32;
+        #[obce(id = 0x2)]
+        fn second(&mut self) -> u32;
+    }
+
+    assert_eq!(
+        <dyn Trait as ExtensionDescription>::METHODS,
+        &[
+            MethodMetadata {
+                name: "first",
+                id: 0x1,
+                view: true,
+            },
+            MethodMetadata {
+                name: "second",
+                id: 0x2,
+                view: false,
+            },
+        ]
+    );
+}
+
+#[test]
+fn definition_custom_cfg() {
+    #[obce::definition(substrate_cfg = all(), ink_cfg = any())]
+    pub trait Trait {
+        fn first(&mut self) -> u32;
+    }
+
+    struct Extension;
+
+    impl Trait for Extension {
+        fn first(&mut self) -> u32 {
+            1
+        }
+    }
+
+    assert_eq!(Extension.first(), 1);
+}
+
+#[test]
+fn definition_both_sides() {
+    #[obce::definition(both, ink_cfg = any())]
+    #[allow(dead_code)]
+    pub trait Trait {
+        #[obce(id = 0x13)]
+        fn first(&mut self) -> u32;
+    }
+
+    assert_eq!(<dyn substrate::Trait as MethodDescription<1877126689>>::ID, 0x13);
+}
+
+#[derive(scale_info::TypeInfo)]
+pub struct AccountId(pub [u8; 32]);
+
+#[derive(scale_info::TypeInfo)]
+pub struct Balance(pub u128);
+
+#[test]
+fn definition_type_mapping_keeps_declared_types() {
+    #[obce::definition]
+    #[allow(dead_code)]
+    pub trait Mapped {
+        #[obce(ink_type = Balance, substrate_type = Balance)]
+        fn first(&mut self, #[obce(ink_type = [u8; 32], substrate_type = "[u8; 32]")] who: AccountId) -> u128;
+    }
+
+    let input: <dyn Mapped as MethodDescription<1877126689>>::Input = AccountId([0; 32]);
+    let output: <dyn Mapped as MethodDescription<1877126689>>::Output = 0u128;
+
+    assert_eq!(input.0, [0; 32]);
+    assert_eq!(output, 0);
+}
+
+#[test]
+fn definition_method_aliases() {
+    #[obce::definition]
+    #[allow(dead_code)]
+    pub trait Trait {
+        #[obce(id = 0x13, aliases(0x12, "old_first"))]
+        fn first(&mut self) -> u32;
+        fn second(&mut self) -> u32;
+    }
+
+    assert_eq!(<dyn Trait as MethodDescription<1877126689>>::ID, 0x13);
+    assert_eq!(<dyn Trait as MethodDescription<1877126689>>::ALIASES, &[0x12, 0x2113]);
+    assert_eq!(<dyn Trait as MethodDescription<1041003283>>::ALIASES, &[]);
+}
+
+#[test]
+fn definition_legacy_raw_ids() {
+    #[obce::definition(legacy)]
+    #[allow(dead_code)]
+    pub trait Trait {
+        #[obce(raw_id = 0x0001_0003)]
+        fn first(&mut self) -> u32;
+        #[obce(raw_id = 0x0001_0004)]
+        fn second(&mut self) -> u32;
+    }
+
+    assert_eq!(<dyn Trait as ExtensionDescription>::ID, 0x1);
+    assert_eq!(<dyn Trait as MethodDescription<1877126689>>::ID, 0x3);
+    assert_eq!(<dyn Trait as MethodDescription<1041003283>>::ID, 0x4);
+}
+
+#[obce::definition(id = 0x13, lock = "tests/obce.lock")]
+#[allow(dead_code)]
+pub trait Locked {
+    #[obce(id = 0x1, aliases(0x12))]
+    fn first(&mut self) -> u32;
+    fn second(&mut self) -> u32;
+    // New methods are not in the lockfile yet, but they don't break the check.
+    #[obce(id = 0x2)]
+    fn third(&mut self) -> u32;
+}
+
+#[obce::definition(legacy, lock = "tests/obce.lock")]
+#[allow(dead_code)]
+pub trait LockedLegacy {
+    #[obce(raw_id = 0x0001_0003)]
+    fn first(&mut self) -> u32;
+}
+
+#[test]
+fn definition_ids_match_lockfile() {
+    assert_eq!(<dyn Locked as ExtensionDescription>::ID, 0x13);
+    assert_eq!(<dyn Locked as MethodDescription<1877126689>>::ID, 0x1);
+    assert_eq!(<dyn LockedLegacy as ExtensionDescription>::ID, 0x1);
+}
+
+#[derive(Debug, PartialEq, Eq)]
+pub struct RandomReadErr;
+
+impl From<RandomReadErr> for u32 {
+    fn from(_: RandomReadErr) -> Self {
+        2
+    }
+}
+
+#[test]
+fn from_ink_keeps_ids_and_wraps_outputs() {
+    #[obce::from_ink]
+    #[allow(dead_code)]
+    pub trait RandExtension {
+        type ErrorCode = RandomReadErr;
+
+        #[ink(extension = 1101, returns_result = false)]
+        fn fetch_random(subject: [u8; 32]) -> [u8; 32];
+
+        #[ink(extension = 1102, handle_status = false)]
+        fn seed() -> u32;
+
+        #[ink(extension = 1103)]
+        fn checked_seed() -> Result<u32, RandomReadErr>;
+    }
+
+    struct Extension;
+
+    // Methods that handle the status code return the error code.
+    impl RandExtension for Extension {
+        fn fetch_random(&mut self, _: [u8; 32]) -> Result<[u8; 32], RandomReadErr> {
+            Err(RandomReadErr)
+        }
+
+        fn seed(&mut self) -> u32 {
+            13
+        }
+
+        fn checked_seed(&mut self) -> Result<u32, RandomReadErr> {
+            Ok(13)
+        }
+    }
+
+    assert_eq!(<dyn RandExtension as ExtensionDescription>::ID, 0);
+    assert_eq!(<dyn RandExtension as MethodDescription<63548885>>::ID, 1101);
+    assert_eq!(<dyn RandExtension as MethodDescription<81281899>>::ID, 1102);
+    assert_eq!(Extension.seed(), 13);
+    assert_eq!(Extension.checked_seed(), Ok(13));
+}
@@ -0,0 +1,8 @@
+// Errors of the substrate side are checked, so the tests require it.
+#![cfg(feature = "substrate")]
+
+#[test]
+fn ui() {
+    let t = trybuild::TestCases::new();
+    t.compile_fail("tests/ui/*.rs");
+}
@@ -0,0 +1,9 @@
+#[derive(scale::Encode)]
+pub struct Error;
+
+#[obce::definition(error_code = Error)]
+pub trait Trait {
+    fn first(&mut self) -> Result<u32, Error>;
+}
+
+fn main() {}
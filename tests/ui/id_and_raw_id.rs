@@ -0,0 +1,8 @@
+#[obce::definition]
+pub trait Trait {
+    #[obce(id = 0x1)]
+    #[obce(raw_id = 0x10001)]
+    fn first(&mut self) -> u64;
+}
+
+fn main() {}
@@ -0,0 +1,9 @@
+const ID: u16 = 0x13;
+
+#[obce::definition]
+pub trait Trait {
+    #[obce(id = ID)]
+    fn first(&mut self) -> u64;
+}
+
+fn main() {}
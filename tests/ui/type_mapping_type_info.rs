@@ -0,0 +1,9 @@
+pub struct Balance(pub u128);
+
+#[obce::definition]
+pub trait Trait {
+    #[obce(substrate_type = Balance)]
+    fn first(&mut self) -> u128;
+}
+
+fn main() {}
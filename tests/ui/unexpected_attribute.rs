@@ -0,0 +1,7 @@
+#[obce::definition]
+pub trait Trait {
+    #[obce(id = 0x1, output_weigth_per_byte = 10)]
+    fn first(&mut self) -> u64;
+}
+
+fn main() {}